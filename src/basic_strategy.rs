@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 
 use crate::hand::Hand;
-use crate::rules::BlackjackRules;
+use crate::rules::{BlackjackRules, DoublePolicy};
 use crate::types::*;
 
 static BS_TABLE_CSV_1D_H17_NDAS_D10: &'static [u8] = include_bytes!("charts/bs_1d_h17_ndas_d10.csv");
 static BS_TABLE_CSV_6D_H17_DAS_DANY: &'static [u8] = include_bytes!("charts/bs_6d_h17_das_dany.csv");
 
-#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, serde::Serialize, serde::Deserialize)]
 pub enum BasicStrategyHand {
     Hard(u32),
     Soft(u32),
@@ -18,7 +21,7 @@ pub enum BasicStrategyHand {
 
 type BasicStrategyHandType = fn (u32) -> BasicStrategyHand;
 
-#[derive(PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, serde::Serialize, serde::Deserialize)]
 pub struct BasicStrategyChartKey {
     pub hand: BasicStrategyHand,
     pub upcard: Rank,
@@ -30,6 +33,34 @@ pub struct BasicStrategyChart {
     chart: HashMap<BasicStrategyChartKey, Vec<Action>>,
 }
 
+/// The on-disk JSON shape of a [BasicStrategyChart]. `chart`'s lookup table is keyed on a struct,
+/// which can't serialize as a JSON object key, so the serialized form flattens it into a plain
+/// list of (key, actions) pairs instead.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BasicStrategyChartJson {
+    rules: BlackjackRules,
+    entries: Vec<(BasicStrategyChartKey, Vec<Action>)>,
+}
+
+impl serde::Serialize for BasicStrategyChart {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BasicStrategyChartJson {
+            rules: self.rules,
+            entries: self.chart.iter().map(|(&k, v)| (k, v.clone())).collect(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for BasicStrategyChart {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = BasicStrategyChartJson::deserialize(deserializer)?;
+        Ok(BasicStrategyChart {
+            rules: json.rules,
+            chart: json.entries.into_iter().collect(),
+        })
+    }
+}
+
 impl BasicStrategyChart {
     /// Load a Basic Strategy chart that is included with the executable in `src/charts`.
     pub fn builtin(rules: &BlackjackRules) -> Result<BasicStrategyChart, Box<dyn Error>> {
@@ -37,15 +68,14 @@ impl BasicStrategyChart {
             BlackjackRules {
                 decks: 1,
                 hit_soft_17: true,
-                double_any_hands: false,
-                double_hard_hands_thru_11: 10,
+                double_policy: DoublePolicy::TenEleven,
                 double_after_split: false,
                 ..
             } => BS_TABLE_CSV_1D_H17_NDAS_D10,
             BlackjackRules {
                 decks: 6,
                 hit_soft_17: true,
-                double_any_hands: true,
+                double_policy: DoublePolicy::AnyTwoCards,
                 double_after_split: true,
                 ..
             } => BS_TABLE_CSV_6D_H17_DAS_DANY,
@@ -55,6 +85,22 @@ impl BasicStrategyChart {
         Ok(BasicStrategyChart { rules: rules.clone(), chart: Self::from_bytes(table_bytes)? })
     }
 
+    /// Load a chart for an arbitrary rule set, previously exported with [Self::to_json] or
+    /// hand-authored, instead of requiring one of the CSVs built into the executable.
+    pub fn from_reader<R: Read>(reader: R) -> serde_json::Result<BasicStrategyChart> {
+        serde_json::from_reader(reader)
+    }
+
+    /// Load a chart from a JSON file on disk. See [Self::from_reader].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<BasicStrategyChart, Box<dyn Error>> {
+        Ok(Self::from_reader(File::open(path)?)?)
+    }
+
+    /// Serialize this chart to a JSON string, preserving multi-action backup cells.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
     fn from_bytes(bytes: &[u8]) -> Result<HashMap<BasicStrategyChartKey, Vec<Action>>, Box<dyn Error>> {
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(false)
@@ -147,18 +193,24 @@ impl BasicStrategyChart {
     pub fn context_basic_play(&self, hand: &Hand, dealer_up: Rank, num_hands: u32) -> Action {
         let can_double = hand.cards.len() == 2
             && (self.rules.double_after_split || num_hands == 1)
-            && (self.rules.double_any_hands ||
-            (hand.total() >= self.rules.double_hard_hands_thru_11 && hand.total() <= 11));
+            && self.rules.double_policy.allows(hand.total());
         let is_splittable_pair = num_hands < match hand.is_pair() {
             Some(A) => self.rules.split_aces_limit,
             Some(_) => self.rules.split_hands_limit,
             None => 1,
         };
+        // Surrender is only offered on the very first decision of an untouched two-card hand.
+        // Early surrender is offered before the dealer's hand is known to be a Blackjack, so it
+        // subsumes late surrender; either ruleset is enough to allow the action here.
+        let can_surrender = hand.cards.len() == 2
+            && num_hands == 1
+            && (self.rules.late_surrender || self.rules.early_surrender);
 
         let action_list = self.basic_plays(hand, dealer_up);
         let first_allowed_action = action_list.iter().filter(|a| match a {
             Action::Double => { can_double }
             Action::Split => { is_splittable_pair }
+            Action::Surrender => { can_surrender }
             _ => true
         }).next();
 
@@ -263,14 +315,15 @@ pub fn int_to_rank_str(rank: Rank) -> String {
     }
 }
 
-/// Convert Action characters (H, S, D, P) to their action, optionally with a second action when
-/// the table specifies a backup
+/// Convert Action characters (H, S, D, P, R) to their action, optionally with a second action
+/// when the table specifies a backup (e.g. "Rh" means "surrender if allowed, otherwise hit").
 fn csv_actions_parse(csv_str: &str) -> Vec<Action> {
     csv_str.chars().map(|c| match c {
         'S' | 's' => Action::Stand,
         'H' | 'h' => Action::Hit,
         'D' | 'd' => Action::Double,
         'P' | 'p' => Action::Split,
+        'R' | 'r' => Action::Surrender,
         unknown => panic!("Unknown Action specifier in basic strategy chart: '{}' (in '{}')", unknown, csv_str)
     }).collect()
 }
@@ -285,6 +338,7 @@ fn to_letters(actions: &Vec<Action>) -> String {
         Action::Hit => 'H',
         Action::Double => 'D',
         Action::Split => 'P',
+        Action::Surrender => 'R',
     }).enumerate().map(|(n, a)|
         if n > 0 {
             a.to_ascii_lowercase()
@@ -296,10 +350,12 @@ fn to_letters(actions: &Vec<Action>) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::basic_strategy::{Action, BasicStrategyChart, csv_actions_parse, to_letters};
+    use std::collections::HashMap;
+
+    use crate::basic_strategy::{Action, BasicStrategyChart, BasicStrategyChartKey, BasicStrategyHand, csv_actions_parse, to_letters};
     use crate::hand;
     use crate::hand::Hand;
-    use crate::rules::RULES_6D_H17_DAS_DANY;
+    use crate::rules::{BlackjackRules, RULES_6D_H17_DAS_DANY};
     use crate::types::{A, T};
 
     #[test]
@@ -377,6 +433,8 @@ mod tests {
 
         assert_eq!(csv_actions_parse("Dh"), [Action::Double, Action::Hit]);
         assert_eq!(csv_actions_parse("Pdh"), [Action::Split, Action::Double, Action::Hit]);
+        assert_eq!(csv_actions_parse("R"), [Action::Surrender]);
+        assert_eq!(csv_actions_parse("Rh"), [Action::Surrender, Action::Hit]);
     }
 
     #[test]
@@ -384,4 +442,46 @@ mod tests {
     fn test_actions_parse_invalid() {
         csv_actions_parse("E");
     }
+
+    #[test]
+    fn test_context_basic_play_surrender() {
+        let mut chart = HashMap::new();
+        chart.insert(
+            BasicStrategyChartKey { hand: BasicStrategyHand::Hard(16), upcard: T },
+            vec![Action::Surrender, Action::Hit],
+        );
+
+        let with_surrender = BasicStrategyChart {
+            rules: BlackjackRules { late_surrender: true, ..RULES_6D_H17_DAS_DANY },
+            chart: chart.clone(),
+        };
+        assert_eq!(with_surrender.context_basic_play(&hand![T, 6], T, 1), Action::Surrender);
+        // A second hand (from a split) isn't allowed to surrender even when the ruleset is.
+        assert_eq!(with_surrender.context_basic_play(&hand![T, 6], T, 2), Action::Hit);
+
+        let without_surrender = BasicStrategyChart {
+            rules: BlackjackRules { late_surrender: false, early_surrender: false, ..RULES_6D_H17_DAS_DANY },
+            chart,
+        };
+        assert_eq!(without_surrender.context_basic_play(&hand![T, 6], T, 1), Action::Hit);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut chart = HashMap::new();
+        chart.insert(
+            BasicStrategyChartKey { hand: BasicStrategyHand::Hard(16), upcard: T },
+            vec![Action::Surrender, Action::Hit],
+        );
+        let original = BasicStrategyChart { rules: RULES_6D_H17_DAS_DANY, chart };
+
+        let json = original.to_json().expect("Couldn't serialize chart");
+        let round_tripped = BasicStrategyChart::from_reader(json.as_bytes())
+            .expect("Couldn't deserialize chart");
+
+        assert_eq!(
+            round_tripped.context_basic_play(&hand![T, 6], T, 1),
+            Action::Surrender,
+        );
+    }
 }
@@ -235,16 +235,7 @@ fn can_double(player_hand: &PartialHand, num_hands: i32) -> bool {
         return false;
     }
 
-    if RULES.double_any_hands {
-        return true;
-    }
-
-    let total = player_hand.total();
-    if total >= RULES.double_hard_hands_thru_11 && total <= 11 {
-        return true;
-    }
-
-    false
+    RULES.double_policy.allows(player_hand.total())
 }
 
 fn can_split(player_hand: &PartialHand, num_hands: i32) -> bool {
@@ -1,16 +1,21 @@
+use std::collections::HashMap;
 use std::sync::Mutex;
 
+use enum_map::enum_map;
 use memoize::lazy_static::lazy_static;
 use memoize::memoize;
 
 use crate::basic_strategy::BasicStrategyChart;
 use crate::deck::Deck;
+use crate::hand;
+use crate::hand::canonical_hand::CanonicalHand;
 use crate::hand::composition_hashed::CompositionHashedHand;
 use crate::hand::Hand;
+use crate::perfect_strategy;
 use crate::perfect_strategy::perfect_play;
 use crate::RULES;
 use crate::shoe;
-use crate::types::{Action, Rank};
+use crate::types::{A, additive_value, Action, Rank, RANKS, T};
 
 pub fn hand_composition_play(hand: &Hand, num_hands: u32, dealer_up: Rank, num_decks: u32) -> Action {
     composition_play(CompositionHashedHand::from(hand), num_hands, dealer_up, num_decks)
@@ -30,12 +35,28 @@ fn composition_play(
     let concrete_hand = Hand::from(hashed_hand);
     let mut deck = shoe!(num_decks);
 
-    deck.card_counts[dealer_up as usize] -= 1;
+    deck.remove_one(dealer_up);
     for card in &concrete_hand.cards {
-        deck.card_counts[*card as usize] -= 1;
+        deck.remove_one(*card);
     }
 
-    let action = perfect_play(&concrete_hand, num_hands, dealer_up, &deck).action;
+    let canonical_hand = CanonicalHand::from_cards(&concrete_hand);
+    let mut splits_allowed = 0;
+    let allowed_actions = enum_map! {
+        Action::Stand => true,
+        Action::Hit => true,
+        Action::Double => concrete_hand.cards.len() == 2
+            && (RULES.double_after_split || num_hands == 1)
+            && RULES.double_policy.allows(concrete_hand.total()),
+        Action::Split => match concrete_hand.is_pair() {
+            Some(A) => { splits_allowed = RULES.split_aces_limit - num_hands; splits_allowed > 0 },
+            Some(_) => { splits_allowed = RULES.split_hands_limit - num_hands; splits_allowed > 0 },
+            None => false,
+        },
+        Action::Surrender => RULES.late_surrender && perfect_strategy::can_surrender(&canonical_hand, num_hands),
+    };
+
+    let action = perfect_play(allowed_actions, &canonical_hand, splits_allowed, dealer_up, &deck).action;
 
     // TODO: Reuse comparison code
     // let bs_action = BS_CHART.lock().unwrap().context_basic_play(&concrete_hand, dealer_up, num_hands);
@@ -45,3 +66,84 @@ fn composition_play(
 
     action
 }
+
+/// A layer of composition-dependent strategy overrides on top of a [BasicStrategyChart]. The
+/// basic chart only keys on (total, soft/hard/pair, upcard), but a handful of hands are known to
+/// play differently depending on exactly which ranks make up that total - because those ranks are
+/// now missing from the shoe, which shifts what the dealer and any subsequent draws are likely to
+/// be. This only has teeth in single- (or few-) deck games; in a 6-8 deck shoe, removing one or
+/// two cards barely moves the composition and every override below collapses back to the basic
+/// chart's own play.
+pub struct CompositionStrategyChart {
+    basic: BasicStrategyChart,
+    overrides: HashMap<(CompositionHashedHand, Rank), Action>,
+}
+
+impl CompositionStrategyChart {
+    /// Compute the overrides against `deck`'s current composition and layer them on `basic`.
+    ///
+    /// Encodes the three canonical composition-dependent plays: hard 16 vs 10 (the two-card T-6
+    /// and 9-7), and hard 12 vs 4/5/6 and hard 15 vs 10 (which flip between standing and hitting
+    /// depending on the specific ranks present). Each override is computed by asking the
+    /// perfect-play EV engine for the truly optimal action against the exact two-card
+    /// composition, rather than hardcoded, so it stays correct as `deck` changes. Only unpaired
+    /// two-card hands get overrides; hands of three or more cards always fall back to `basic`.
+    pub fn build(basic: BasicStrategyChart, deck: &Deck) -> Self {
+        let mut overrides = HashMap::new();
+        for &(total, upcards) in &[(16u32, &[T][..]), (15, &[T]), (12, &[4, 5, 6])] {
+            for &upcard in upcards {
+                for (r1, r2) in two_card_hard_totals(total) {
+                    let hand = hand![r1, r2];
+                    let key = (CompositionHashedHand::from(&hand), upcard);
+                    overrides.insert(key, two_card_hard_total_play(total, r1, r2, upcard, deck));
+                }
+            }
+        }
+        Self { basic, overrides }
+    }
+
+    /// The optimal play for `hand` vs `dealer_up`, consulting the composition-dependent overrides
+    /// before falling back to the underlying basic strategy chart.
+    pub fn context_play(&self, hand: &Hand, dealer_up: Rank, num_hands: u32) -> Action {
+        if num_hands == 1 {
+            let key = (CompositionHashedHand::from(hand), dealer_up);
+            if let Some(&action) = self.overrides.get(&key) {
+                return action;
+            }
+        }
+        self.basic.context_basic_play(hand, dealer_up, num_hands)
+    }
+}
+
+/// Every unpaired, non-soft two-card rank combination that hard-totals `total`, each returned
+/// exactly once with the lower rank first.
+fn two_card_hard_totals(total: u32) -> Vec<(Rank, Rank)> {
+    let mut pairs = vec![];
+    for r1 in RANKS {
+        for r2 in RANKS {
+            if r1 >= r2 || r1 == A || r2 == A {
+                continue;
+            }
+            if additive_value(r1) + additive_value(r2) == total {
+                pairs.push((r1, r2));
+            }
+        }
+    }
+    pairs
+}
+
+/// The truly optimal play for the exact two-card hand `r1`+`r2` (hard-totaling `total`) against
+/// `upcard`, given the rest of `deck`.
+fn two_card_hard_total_play(total: u32, r1: Rank, r2: Rank, upcard: Rank, deck: &Deck) -> Action {
+    let deck_after = deck.removed(upcard).removed(r1).removed(r2);
+    let allowed_actions = enum_map! {
+        Action::Stand => true,
+        Action::Hit => true,
+        Action::Double => RULES.double_policy.allows(total),
+        Action::Split => false,
+        Action::Surrender => RULES.late_surrender,
+    };
+    perfect_strategy::perfect_play(
+        allowed_actions, &CanonicalHand::Hard2Card(total), 0, upcard, &deck_after,
+    ).action
+}
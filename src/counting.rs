@@ -0,0 +1,607 @@
+//! Card-counting support: tag-based counting systems, a true-count-driven bet ramp, and index-play
+//! deviations from the basic-strategy chart.
+
+use std::fmt::{Display, Formatter};
+
+use enum_map::{enum_map, EnumMap};
+
+use crate::basic_strategy::{BasicStrategyChart, BasicStrategyChartKey, BasicStrategyHand, int_to_rank_str};
+use crate::deck::Deck;
+use crate::hand;
+use crate::hand::canonical_hand::CanonicalHand;
+use crate::hand::Hand;
+use crate::perfect_strategy;
+use crate::perfect_strategy::perfect_play;
+use crate::shoe;
+use crate::types::{Action, Rank, A, RANKS, T};
+use crate::RULES;
+
+/// Assigns a per-rank tag that a player adds to a running count as cards leave the deck.
+pub trait CountingSystem: Send + Sync {
+    fn tag(&self, rank: Rank) -> i32;
+}
+
+/// The classic Hi-Lo system: low cards are good for the player to see leave the deck (count up),
+/// tens and aces are bad (count down).
+pub struct HiLo;
+
+impl CountingSystem for HiLo {
+    fn tag(&self, rank: Rank) -> i32 {
+        match rank {
+            T | A => -1,
+            2..=6 => 1,
+            7..=9 => 0,
+            _ => unreachable!("not a valid Rank"),
+        }
+    }
+}
+
+/// Omega II, a balanced multi-level system with extra weight on small and ten-valued cards.
+pub struct OmegaII;
+
+impl CountingSystem for OmegaII {
+    fn tag(&self, rank: Rank) -> i32 {
+        match rank {
+            T => -2,
+            A | 8 => 0,
+            2 | 3 | 7 => 1,
+            4 | 5 | 6 => 2,
+            9 => -1,
+            _ => unreachable!("not a valid Rank"),
+        }
+    }
+}
+
+/// The running count of `current` relative to a freshly-shuffled `num_decks`-deck shoe, under
+/// `system`. Derived from the deck's current composition rather than tracked incrementally card-
+/// by-card, since within one shoe the two are equivalent (every card that has left the deck has
+/// been removed exactly once) and this avoids plumbing a counting system into every `Deck`.
+pub fn running_count(current: &Deck, num_decks: u32, system: &impl CountingSystem) -> i32 {
+    let starting: Deck = shoe!(num_decks);
+    RANKS.map(|rank| {
+        let removed = starting.card_counts[rank as usize] as i32 - current.card_counts[rank as usize] as i32;
+        system.tag(rank) * removed
+    }).sum()
+}
+
+/// The true count: running count divided by decks remaining in `current`.
+pub fn true_count(current: &Deck, num_decks: u32, system: &impl CountingSystem) -> f64 {
+    let decks_remaining = (current.len() as f64 / 52.0).max(1.0 / 52.0);
+    running_count(current, num_decks, system) as f64 / decks_remaining
+}
+
+/// Maps a true count to a bet size in units. Steps are `(true_count_threshold, bet_units)` pairs;
+/// the bet used is that of the highest threshold not exceeding the current true count, so the
+/// lowest threshold (typically `f64::NEG_INFINITY`) acts as the base bet.
+#[derive(Clone)]
+pub struct BetRamp {
+    pub steps: Vec<(f64, f64)>,
+}
+
+impl BetRamp {
+    pub fn new(mut steps: Vec<(f64, f64)>) -> Self {
+        steps.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        BetRamp { steps }
+    }
+
+    /// A simple flat-then-ramping spread: 1 unit below `ramp_start`, `1 + unit_per_count` units
+    /// from `ramp_start`, then `unit_per_count` more units for every further point of true count,
+    /// capped at `max_units`.
+    pub fn linear(ramp_start: f64, unit_per_count: f64, max_units: f64) -> Self {
+        let mut steps = vec![(f64::NEG_INFINITY, 1.0)];
+        let mut tc = ramp_start;
+        let mut bet = 1.0 + unit_per_count;
+        while bet <= max_units {
+            steps.push((tc, bet));
+            tc += 1.0;
+            bet += unit_per_count;
+        }
+        BetRamp::new(steps)
+    }
+
+    pub fn bet_units(&self, true_count: f64) -> f64 {
+        self.steps.iter().rev()
+            .find(|(threshold, _)| true_count >= *threshold)
+            .map(|(_, units)| *units)
+            .unwrap_or(1.0)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Comparison {
+    GreaterOrEqual,
+    LessOrEqual,
+}
+
+/// A single index-play deviation: at the given hand/upcard, play `action` once the true count
+/// crosses `threshold` in the direction given by `comparison`.
+#[derive(Clone)]
+pub struct IndexPlay {
+    pub key: BasicStrategyChartKey,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    pub action: Action,
+}
+
+/// A set of index-play deviations (the "Illustrious 18"-style departures from basic strategy)
+/// plus an insurance threshold, applied on top of a [BasicStrategyChart].
+#[derive(Clone)]
+pub struct DeviationTable {
+    pub plays: Vec<IndexPlay>,
+    /// Take insurance once the true count is at least this high.
+    pub insurance_threshold: f64,
+}
+
+impl DeviationTable {
+    /// The classic Hi-Lo "Illustrious 18"-style deviations plus insurance.
+    pub fn classic_hi_lo() -> Self {
+        use Action::*;
+        use BasicStrategyHand::*;
+        use Comparison::*;
+
+        let play = |hand, upcard, comparison, threshold, action| IndexPlay {
+            key: BasicStrategyChartKey { hand, upcard },
+            comparison,
+            threshold,
+            action,
+        };
+
+        DeviationTable {
+            insurance_threshold: 3.0,
+            plays: vec![
+                play(Hard(16), T, GreaterOrEqual, 0.0, Stand),
+                play(Hard(15), T, GreaterOrEqual, 4.0, Stand),
+                play(Hard(12), 3, GreaterOrEqual, 2.0, Stand),
+                play(Hard(12), 2, GreaterOrEqual, 3.0, Stand),
+                play(Hard(12), 4, LessOrEqual, 0.0, Hit),
+                play(Hard(10), T, GreaterOrEqual, 4.0, Double),
+                play(Hard(10), A, GreaterOrEqual, 3.0, Double),
+                play(Hard(9), 2, GreaterOrEqual, 1.0, Double),
+                play(Hard(9), 7, GreaterOrEqual, 3.0, Double),
+            ],
+        }
+    }
+
+    pub fn take_insurance(&self, true_count: f64) -> bool {
+        true_count >= self.insurance_threshold
+    }
+
+    /// The deviation-adjusted play for this hand, falling back to `chart`'s basic play when no
+    /// deviation applies at the current true count.
+    pub fn decide(&self, chart: &BasicStrategyChart, hand: &Hand, dealer_up: Rank, num_hands: u32, true_count: f64) -> Action {
+        let hand_key = if hand.is_pair().is_some() {
+            BasicStrategyHand::from(hand)
+        } else {
+            BasicStrategyHand::from_unsplittable(hand)
+        };
+
+        for play in &self.plays {
+            if play.key.hand != hand_key || play.key.upcard != dealer_up {
+                continue;
+            }
+            let triggered = match play.comparison {
+                Comparison::GreaterOrEqual => true_count >= play.threshold,
+                Comparison::LessOrEqual => true_count <= play.threshold,
+            };
+            if triggered {
+                return play.action;
+            }
+        }
+
+        chart.context_basic_play(hand, dealer_up, num_hands)
+    }
+}
+
+/// Bundles everything [crate::simulation::play_hand] needs to play under a counting system: the
+/// basic chart to deviate from, the deviations themselves, the counting system used to derive the
+/// true count, and the bet ramp the true count drives.
+pub struct CountingPlay<'a> {
+    pub chart: &'a BasicStrategyChart,
+    pub deviations: &'a DeviationTable,
+    pub system: &'a dyn CountingSystem,
+    pub bet_ramp: &'a BetRamp,
+    pub num_decks: u32,
+}
+
+/// True-count buckets, clamped to `[COUNT_BUCKET_MIN, COUNT_BUCKET_MAX]`, that
+/// [CountBucketStats] tracks separately.
+pub const COUNT_BUCKET_MIN: i32 = -10;
+pub const COUNT_BUCKET_MAX: i32 = 10;
+const NUM_COUNT_BUCKETS: usize = (COUNT_BUCKET_MAX - COUNT_BUCKET_MIN + 1) as usize;
+
+/// Per-true-count-bucket totals of units wagered and ROI, so a given bet ramp/deviation set can be
+/// judged bucket-by-bucket rather than just in aggregate.
+#[derive(Clone, Copy, Debug)]
+pub struct CountBucketStats {
+    pub units_wagered: [f64; NUM_COUNT_BUCKETS],
+    pub roi: [f64; NUM_COUNT_BUCKETS],
+}
+
+impl Default for CountBucketStats {
+    fn default() -> Self {
+        CountBucketStats { units_wagered: [0.0; NUM_COUNT_BUCKETS], roi: [0.0; NUM_COUNT_BUCKETS] }
+    }
+}
+
+impl CountBucketStats {
+    fn bucket_index(true_count: f64) -> usize {
+        let clamped = (true_count.round() as i32).clamp(COUNT_BUCKET_MIN, COUNT_BUCKET_MAX);
+        (clamped - COUNT_BUCKET_MIN) as usize
+    }
+
+    pub fn observe(&mut self, true_count: f64, bet_units: f64, roi: f64) {
+        let i = Self::bucket_index(true_count);
+        self.units_wagered[i] += bet_units;
+        self.roi[i] += roi;
+    }
+}
+
+impl std::ops::AddAssign for CountBucketStats {
+    fn add_assign(&mut self, rhs: Self) {
+        for i in 0..NUM_COUNT_BUCKETS {
+            self.units_wagered[i] += rhs.units_wagered[i];
+            self.roi[i] += rhs.roi[i];
+        }
+    }
+}
+
+impl std::ops::Add for CountBucketStats {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+/// How far outward (in running count) [DeviationTable::generate] sweeps looking for a deviation
+/// before giving up on a cell. Wide enough to find every true-count deviation a counting system
+/// could plausibly call for this side of a fresh shoe.
+const MAX_RUNNING_COUNT_SWEEP: i32 = 40;
+
+/// Remove `count` cards from `deck`, spread across `ranks` in proportion to how many of each are
+/// still left, so the residual deck stays a realistic shape instead of draining one rank bare
+/// before touching the next. Flooring each rank's share can leave a few cards unaccounted for;
+/// those are handed out one at a time to whichever ranks still have copies left.
+fn remove_proportionally(deck: &mut Deck, ranks: &[Rank], count: u32) {
+    if ranks.is_empty() || count == 0 {
+        return;
+    }
+
+    let available: u32 = ranks.iter().map(|&r| deck.card_counts[r as usize]).sum();
+    let count = count.min(available);
+    if count == 0 {
+        return;
+    }
+
+    let mut removed_so_far = 0;
+    for &r in ranks {
+        let share = (count as f64 * deck.card_counts[r as usize] as f64 / available as f64).floor() as u32;
+        for _ in 0..share {
+            deck.remove_one(r);
+        }
+        removed_so_far += share;
+    }
+
+    let mut leftover = count - removed_so_far;
+    for &r in ranks {
+        if leftover == 0 {
+            break;
+        }
+        if deck.card_counts[r as usize] > 0 {
+            deck.remove_one(r);
+            leftover -= 1;
+        }
+    }
+}
+
+/// A representative residual `Deck`, carved out of a fresh `num_decks`-deck shoe, whose Hi-Lo-style
+/// running count under `system` is `running_count` (as close as the shoe's composition allows).
+/// Only ranks whose tag shares `running_count`'s sign are touched - removing a card with an
+/// opposing or zero tag couldn't have produced this running count - and cards are drained from the
+/// heaviest-magnitude tag group first, spread proportionally within each group (see
+/// [remove_proportionally]) since those ranks move the count fastest per card seen.
+fn deck_for_running_count(num_decks: u32, system: &impl CountingSystem, running_count: i32) -> Deck {
+    let mut deck: Deck = shoe!(num_decks);
+    if running_count == 0 {
+        return deck;
+    }
+
+    let sign = running_count.signum();
+    let mut remaining = running_count.unsigned_abs();
+
+    let mut tags: Vec<i32> = RANKS.map(|r| system.tag(r)).filter(|t| t.signum() == sign).collect();
+    tags.sort_unstable_by_key(|&t| std::cmp::Reverse(t.unsigned_abs()));
+    tags.dedup();
+
+    for tag in tags {
+        if remaining == 0 {
+            break;
+        }
+
+        let group: Vec<Rank> = RANKS.filter(|&r| system.tag(r) == tag).collect();
+        let group_capacity: u32 = group.iter().map(|&r| deck.card_counts[r as usize]).sum();
+        let tag_abs = tag.unsigned_abs();
+        let cards_to_remove = (remaining / tag_abs).min(group_capacity);
+
+        remove_proportionally(&mut deck, &group, cards_to_remove);
+        remaining -= cards_to_remove * tag_abs;
+    }
+
+    deck
+}
+
+/// Every distinct reachable 2-card starting hand, as a concrete [Hand] (so it can be looked up in
+/// a [BasicStrategyChart]) rather than the [CanonicalHand] [crate::perfect_strategy::strategy_chart]
+/// sweeps over. Excludes natural Blackjack, which is never a player decision point.
+fn two_card_starting_hands() -> Vec<Hand> {
+    let mut seen = Vec::new();
+    let mut hands = Vec::new();
+    for first in RANKS {
+        for second in RANKS {
+            let concrete = hand![first, second];
+            let canonical = CanonicalHand::from_cards(&concrete);
+            if canonical == CanonicalHand::Blackjack || seen.contains(&canonical) {
+                continue;
+            }
+            seen.push(canonical);
+            hands.push(concrete);
+        }
+    }
+    hands
+}
+
+/// The actions allowed on `hand`'s very first decision, and how many further splits it could take,
+/// mirroring the gating [crate::perfect_strategy::strategy_chart] applies - duplicated rather than
+/// shared since that function's loop body isn't itself a reusable unit.
+fn first_decision_actions(hand: CanonicalHand) -> (EnumMap<Action, bool>, u32) {
+    let mut splits_allowed = 0;
+    let allowed_actions = enum_map! {
+        Action::Stand => true,
+        Action::Hit => true,
+        Action::Double => RULES.double_policy.allows(hand.total()),
+        Action::Split => match hand {
+            CanonicalHand::Pair(A) => { splits_allowed = RULES.split_aces_limit - 1; splits_allowed > 0 },
+            CanonicalHand::Pair(_) => { splits_allowed = RULES.split_hands_limit - 1; splits_allowed > 0 },
+            _ => false,
+        },
+        Action::Surrender => RULES.late_surrender && perfect_strategy::can_surrender(&hand, 1),
+    };
+    (allowed_actions, splits_allowed)
+}
+
+/// Sweep the running count outward from zero, one card-equivalent at a time in the direction
+/// `comparison` calls for, until [perfect_play]'s recommendation first differs from
+/// `basic_action`. Returns the true count (not the running count) at which that happens, and the
+/// action perfect play switches to - `None` if it never deviates within [MAX_RUNNING_COUNT_SWEEP].
+fn find_pivot(
+    comparison: Comparison,
+    basic_action: Action,
+    allowed_actions: EnumMap<Action, bool>,
+    hand: CanonicalHand,
+    splits_allowed: u32,
+    upcard: Rank,
+    system: &impl CountingSystem,
+    num_decks: u32,
+) -> Option<(f64, Action)> {
+    let step = match comparison {
+        Comparison::GreaterOrEqual => 1,
+        Comparison::LessOrEqual => -1,
+    };
+
+    let mut running_count = step;
+    while running_count.abs() <= MAX_RUNNING_COUNT_SWEEP {
+        let deck = deck_for_running_count(num_decks, system, running_count);
+        let action = perfect_play(allowed_actions, &hand, splits_allowed, upcard, &deck).action;
+        if action != basic_action {
+            return Some((true_count(&deck, num_decks, system), action));
+        }
+        running_count += step;
+    }
+
+    None
+}
+
+/// The true count at which taking insurance first becomes +EV under `system`, the same kind of
+/// pivot [find_pivot] looks for but driven by [perfect_strategy::insurance_ev] instead of
+/// [perfect_play], since insurance is offered independent of the player's own hand.
+fn generate_insurance_threshold(system: &impl CountingSystem, num_decks: u32) -> f64 {
+    for running_count in 1..=MAX_RUNNING_COUNT_SWEEP {
+        let deck = deck_for_running_count(num_decks, system, running_count);
+        if perfect_strategy::insurance_ev(&deck) > 0.0 {
+            return true_count(&deck, num_decks, system);
+        }
+    }
+    f64::INFINITY
+}
+
+impl DeviationTable {
+    /// Auto-generate an index-number table for `system`, instead of hand-authoring thresholds the
+    /// way [Self::classic_hi_lo] does. For every reachable 2-card starting hand and dealer upcard,
+    /// sweeps the true count outward from zero in both directions (see [find_pivot]) until
+    /// [perfect_play]'s recommendation first flips away from `chart`'s basic play, and records
+    /// each pivot found as an [IndexPlay]. This reproduces Illustrious-18-style tables directly
+    /// from the combinatorial engine for any [CountingSystem], rather than only the one published
+    /// set of deviations [Self::classic_hi_lo] hard-codes.
+    pub fn generate(chart: &BasicStrategyChart, system: &impl CountingSystem, num_decks: u32) -> DeviationTable {
+        let mut plays = Vec::new();
+
+        for hand in two_card_starting_hands() {
+            let canonical = CanonicalHand::from_cards(&hand);
+            let (allowed_actions, splits_allowed) = first_decision_actions(canonical);
+            let basic_key_hand = BasicStrategyHand::from(&hand);
+
+            for upcard in RANKS {
+                let basic_action = chart.context_basic_play(&hand, upcard, 1);
+                let key = BasicStrategyChartKey { hand: basic_key_hand, upcard };
+
+                for comparison in [Comparison::GreaterOrEqual, Comparison::LessOrEqual] {
+                    if let Some((threshold, action)) = find_pivot(
+                        comparison, basic_action, allowed_actions, canonical, splits_allowed, upcard, system, num_decks,
+                    ) {
+                        plays.push(IndexPlay { key, comparison, threshold, action });
+                    }
+                }
+            }
+        }
+
+        DeviationTable { plays, insurance_threshold: generate_insurance_threshold(system, num_decks) }
+    }
+}
+
+impl Display for DeviationTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header_ranks = [2, 3, 4, 5, 6, 7, 8, 9, 0, 1];
+
+        let cell = |hand: BasicStrategyHand, upcard: Rank| -> String {
+            match self.plays.iter().find(|p| p.key == BasicStrategyChartKey { hand, upcard }) {
+                Some(p) => {
+                    let arrow = match p.comparison {
+                        Comparison::GreaterOrEqual => "\u{2265}",
+                        Comparison::LessOrEqual => "\u{2264}",
+                    };
+                    format!("{:?}{}{:+}", p.action, arrow, p.threshold)
+                }
+                None => "-".to_string(),
+            }
+        };
+
+        write!(f, "Hard")?;
+        for upcard in &header_ranks {
+            write!(f, " {:^12}", int_to_rank_str(*upcard))?;
+        }
+        writeln!(f)?;
+        for hard_total in 5..=19 {
+            write!(f, "{:<4}", hard_total)?;
+            for &upcard in &header_ranks {
+                write!(f, " {:^12}", cell(BasicStrategyHand::Hard(hard_total), upcard))?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "Soft")?;
+        for upcard in &header_ranks {
+            write!(f, " {:^12}", int_to_rank_str(*upcard))?;
+        }
+        writeln!(f)?;
+        for soft_total in 13..=20 {
+            write!(f, "{:<4}", soft_total)?;
+            for &upcard in &header_ranks {
+                write!(f, " {:^12}", cell(BasicStrategyHand::Soft(soft_total), upcard))?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "Pair")?;
+        for upcard in &header_ranks {
+            write!(f, " {:^12}", int_to_rank_str(*upcard))?;
+        }
+        writeln!(f)?;
+        for &paired_card in &header_ranks {
+            write!(f, "{:<4}", int_to_rank_str(paired_card))?;
+            for &upcard in &header_ranks {
+                write!(f, " {:^12}", cell(BasicStrategyHand::Pair(paired_card), upcard))?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "\nInsurance \u{2265} {:+}", self.insurance_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::basic_strategy::BasicStrategyHand;
+    use crate::shoe;
+
+    use super::*;
+
+    #[test]
+    fn test_true_count() {
+        // A fresh shoe has seen nothing yet, so both counts are zero regardless of system.
+        let fresh: Deck = shoe!(6);
+        assert_eq!(running_count(&fresh, 6, &HiLo), 0);
+        assert_eq!(true_count(&fresh, 6, &HiLo), 0.0);
+
+        // Removing one deck's worth of small cards (20 cards with a Hi-Lo tag of +1 each) from a
+        // 6-deck shoe leaves 292 cards (292/52 decks), for a true count of 20 / (292/52) ~= 3.56.
+        let mut after_low_cards = fresh;
+        for rank in 2..=6 {
+            for _ in 0..4 {
+                after_low_cards.remove_one(rank);
+            }
+        }
+        assert_eq!(running_count(&after_low_cards, 6, &HiLo), 20);
+        assert_eq!(true_count(&after_low_cards, 6, &HiLo), 20.0 / (292.0 / 52.0));
+    }
+
+    #[test]
+    fn test_bet_ramp() {
+        let ramp = BetRamp::linear(1.0, 1.0, 4.0);
+        assert_eq!(ramp.bet_units(-5.0), 1.0);
+        assert_eq!(ramp.bet_units(0.0), 1.0);
+        assert_eq!(ramp.bet_units(1.0), 2.0);
+        assert_eq!(ramp.bet_units(3.0), 4.0);
+        assert_eq!(ramp.bet_units(100.0), 4.0);
+    }
+
+    #[test]
+    fn test_classic_hi_lo_index_plays() {
+        let table = DeviationTable::classic_hi_lo();
+
+        assert!(!table.take_insurance(2.9));
+        assert!(table.take_insurance(3.0));
+
+        let find = |hand, upcard| table.plays.iter()
+            .find(|p| p.key == BasicStrategyChartKey { hand, upcard })
+            .unwrap_or_else(|| panic!("No index play found"));
+
+        let stand_16_v_t = find(BasicStrategyHand::Hard(16), T);
+        assert_eq!(stand_16_v_t.action, Action::Stand);
+        assert_eq!(stand_16_v_t.comparison, Comparison::GreaterOrEqual);
+        assert_eq!(stand_16_v_t.threshold, 0.0);
+
+        let stand_15_v_t = find(BasicStrategyHand::Hard(15), T);
+        assert_eq!(stand_15_v_t.action, Action::Stand);
+        assert_eq!(stand_15_v_t.threshold, 4.0);
+
+        let stand_12_v_3 = find(BasicStrategyHand::Hard(12), 3);
+        assert_eq!(stand_12_v_3.action, Action::Stand);
+        assert_eq!(stand_12_v_3.threshold, 2.0);
+
+        let stand_12_v_2 = find(BasicStrategyHand::Hard(12), 2);
+        assert_eq!(stand_12_v_2.action, Action::Stand);
+        assert_eq!(stand_12_v_2.threshold, 3.0);
+    }
+
+    #[test]
+    fn test_deck_for_running_count_hits_target_hi_lo() {
+        // A fresh 6-deck shoe has 120 low cards (2-6) and 120 Tens/Aces; removing 24 is well within
+        // capacity, so the running count this produces should land on target exactly.
+        let deck = deck_for_running_count(6, &HiLo, 24);
+        assert_eq!(running_count(&deck, 6, &HiLo), 24);
+
+        let deck = deck_for_running_count(6, &HiLo, -24);
+        assert_eq!(running_count(&deck, 6, &HiLo), -24);
+
+        // Zero is the identity case - nothing removed.
+        let deck = deck_for_running_count(6, &HiLo, 0);
+        assert_eq!(deck, shoe!(6));
+    }
+
+    #[test]
+    fn test_generate_finds_the_classic_hard_16_vs_ten_deviation() {
+        // Standing on a hard 16 vs. a dealer Ten at a true count of 0 or higher is the single
+        // most famous Illustrious 18 play; the auto-generated table should reproduce its
+        // direction even if the exact threshold doesn't match the hand-authored one.
+        let chart = BasicStrategyChart::builtin(&RULES).unwrap();
+        let table = DeviationTable::generate(&chart, &HiLo, 1);
+
+        let deviation = table.plays.iter()
+            .find(|p| p.key == BasicStrategyChartKey { hand: BasicStrategyHand::Hard(16), upcard: T })
+            .expect("Expected a Hard 16 vs. Ten deviation");
+
+        assert_eq!(deviation.action, Action::Stand);
+        assert_eq!(deviation.comparison, Comparison::GreaterOrEqual);
+    }
+}
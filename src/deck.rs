@@ -1,46 +1,203 @@
+use std::hash::{Hash, Hasher};
 use std::ops::Index;
 
+use memoize::lazy_static::lazy_static;
 use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
 
-use crate::types::Rank;
+use crate::types::{Card, Rank, Suit, SUITS};
+
+/// Largest shoe size (in standard 52-card decks) this crate is expected to ever simulate. Bounds
+/// the Zobrist key tables below.
+const MAX_DECKS: u32 = 8;
+
+/// Upper bound on how many copies of a Ten (and its face cards) can be live at once in a shoe of
+/// up to [MAX_DECKS] decks.
+const MAX_COUNT_TENS: usize = 16 * MAX_DECKS as usize + 1;
+
+/// Upper bound on how many copies of any non-Ten rank can be live at once in a shoe of up to
+/// [MAX_DECKS] decks.
+const MAX_COUNT_OTHER: usize = 4 * MAX_DECKS as usize + 1;
+
+/// Random keys for [zobrist_key], one table for Tens (which run up to 16/deck) and one per
+/// remaining rank (which run up to 4/deck).
+struct ZobristTable {
+    tens: [u64; MAX_COUNT_TENS],
+    other: [[u64; MAX_COUNT_OTHER]; 9],
+}
+
+fn build_zobrist_table() -> ZobristTable {
+    let mut rng = rand::thread_rng();
+    let mut tens = [0u64; MAX_COUNT_TENS];
+    for slot in tens.iter_mut() {
+        *slot = rng.gen();
+    }
+    let mut other = [[0u64; MAX_COUNT_OTHER]; 9];
+    for rank_table in other.iter_mut() {
+        for slot in rank_table.iter_mut() {
+            *slot = rng.gen();
+        }
+    }
+    ZobristTable { tens, other }
+}
+
+lazy_static! {
+    static ref ZOBRIST_TABLE: ZobristTable = build_zobrist_table();
+}
+
+/// The Zobrist key for having `count` copies of `rank` left in the shoe.
+fn zobrist_key(rank: Rank, count: u32) -> u64 {
+    if rank == 0 {
+        ZOBRIST_TABLE.tens[count as usize]
+    } else {
+        ZOBRIST_TABLE.other[rank as usize - 1][count as usize]
+    }
+}
+
+/// Hash a full `card_counts` array from scratch. Only needed when building a Deck outside of
+/// [Deck::added]/[Deck::removed]/[Deck::draw], which otherwise maintain `zobrist` incrementally.
+pub fn zobrist_hash(card_counts: &[u32; 10]) -> u64 {
+    let mut hash = 0u64;
+    for (rank, &count) in card_counts.iter().enumerate() {
+        hash ^= zobrist_key(rank as Rank, count);
+    }
+    hash
+}
 
 /// A Deck of cards, represented by the number of cards of each rank left in the Deck.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+///
+/// For pure strategy work this rank-only representation is all that matters and is kept as the
+/// fast path. `suited_cards`, when present, additionally tracks the suit of every remaining card
+/// so that suit-dependent side bets (Perfect Pairs, 21+3) can be evaluated; it is kept in sync
+/// with `card_counts` by [Deck::draw_suited].
+///
+/// `zobrist` is an incrementally maintained hash of `card_counts` (see [zobrist_hash]), kept
+/// up to date by every method that mutates the counts so that [Hash] never has to walk the array.
+/// It is NOT collision-free across different compositions, so [PartialEq]/[Eq] still compare the
+/// real `card_counts`/`suited_cards` rather than trusting the hash alone.
+#[derive(Clone, Debug, Eq)]
 pub struct Deck {
     pub card_counts: [u32; 10],
+    pub suited_cards: Option<Vec<Card>>,
+    zobrist: u64,
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        let card_counts = [0; 10];
+        Deck { card_counts, suited_cards: None, zobrist: zobrist_hash(&card_counts) }
+    }
+}
+
+impl PartialEq for Deck {
+    fn eq(&self, other: &Self) -> bool {
+        self.card_counts == other.card_counts && self.suited_cards == other.suited_cards
+    }
+}
+
+impl Hash for Deck {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.zobrist.hash(state);
+    }
 }
 
 impl Deck {
+    /// Build a Deck from raw counts, computing its initial `zobrist` hash from scratch.
+    pub fn new(card_counts: [u32; 10]) -> Self {
+        Deck { card_counts, suited_cards: None, zobrist: zobrist_hash(&card_counts) }
+    }
+
     pub fn len(&self) -> u32 {
         self.card_counts.iter().sum()
     }
 
+    /// This Deck's incrementally maintained Zobrist hash. NOT collision-free across different
+    /// compositions; only useful as a cheap bucketing key ahead of a real equality check.
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
     /// Pick a random card from this Deck without mutating the Deck.
-    pub fn random_card(&self) -> Rank {
+    pub fn random_card(&self, rng: &mut impl Rng) -> Rank {
         let dist = WeightedIndex::new(self.card_counts).unwrap();
-        dist.sample(&mut rand::thread_rng()) as Rank
+        dist.sample(rng) as Rank
     }
 
     /// Draw a random card from this Deck and remove it from the Deck.
-    pub fn draw(&mut self) -> Rank {
-        let card = self.random_card();
-        self.card_counts[card as usize] -= 1;
+    pub fn draw(&mut self, rng: &mut impl Rng) -> Rank {
+        let card = self.random_card(rng);
+        self.remove_one(card);
+        card
+    }
+
+    /// Draw a random card, including its suit, and remove it from the Deck. Only valid on a Deck
+    /// built with [Deck::full_shoe_with_suits].
+    pub fn draw_suited(&mut self, rng: &mut impl Rng) -> Card {
+        let suited_cards = self.suited_cards.as_mut().expect("Deck was not built with suit tracking");
+        let idx = rng.gen_range(0..suited_cards.len());
+        let card = suited_cards.swap_remove(idx);
+        self.remove_one(card.rank);
         card
     }
 
     /// Get a copy of this Deck with one specific card added.
     pub fn added(&self, rank: Rank) -> Self {
         let mut c = self.clone();
-        c.card_counts[rank as usize] += 1;
+        c.add_one(rank);
         c
     }
 
     /// Get a copy of this Deck with one specific card removed.
     pub fn removed(&self, rank: Rank) -> Self {
         let mut c = self.clone();
-        c.card_counts[rank as usize] -= 1;
+        c.remove_one(rank);
         c
     }
+
+    /// Add one card of `rank` in place, XOR-ing `zobrist` in and out of its old and new key
+    /// rather than rehashing the whole composition. Prefer this (or [Deck::added]) over writing
+    /// `card_counts` directly, which would leave `zobrist` stale.
+    pub(crate) fn add_one(&mut self, rank: Rank) {
+        let old_count = self.card_counts[rank as usize];
+        self.card_counts[rank as usize] += 1;
+        self.zobrist ^= zobrist_key(rank, old_count) ^ zobrist_key(rank, old_count + 1);
+    }
+
+    /// Remove one card of `rank` in place, XOR-ing `zobrist` in and out of its old and new key
+    /// rather than rehashing the whole composition. Prefer this (or [Deck::removed]) over writing
+    /// `card_counts` directly, which would leave `zobrist` stale.
+    pub(crate) fn remove_one(&mut self, rank: Rank) {
+        let old_count = self.card_counts[rank as usize];
+        self.card_counts[rank as usize] -= 1;
+        self.zobrist ^= zobrist_key(rank, old_count) ^ zobrist_key(rank, old_count - 1);
+    }
+
+    /// Build a shoe of `num_decks` standard 52-card decks with full suit detail, for simulating
+    /// suit-dependent side bets. The rank-only `card_counts` stays in sync for the fast paths
+    /// (strategy calculation, dealer/player totals) that don't need suits.
+    ///
+    /// Note: [Rank] collapses Ten/Jack/Queen/King into a single `T` value, so a "pair" of two
+    /// `T`-ranked `Card`s may actually be e.g. a King and a Queen. Side bets that care about exact
+    /// rank (not just blackjack value) are therefore a slight overestimate of real-world pair
+    /// frequency for ten-valued cards.
+    pub fn full_shoe_with_suits(num_decks: u32, rng: &mut impl Rng) -> Self {
+        let mut deck = crate::shoe!(num_decks);
+
+        let mut cards = Vec::with_capacity(deck.len() as usize);
+        for rank in crate::types::RANKS {
+            let copies_per_suit = deck.card_counts[rank as usize] / 4;
+            for &suit in &SUITS {
+                for _ in 0..copies_per_suit {
+                    cards.push(Card { rank, suit });
+                }
+            }
+        }
+        cards.shuffle(rng);
+
+        deck.suited_cards = Some(cards);
+        deck
+    }
 }
 
 impl Index<Rank> for Deck {
@@ -56,7 +213,7 @@ impl Index<Rank> for Deck {
 macro_rules! deck {
     ($ten: expr, $ace: expr, $two: expr, $three: expr, $four: expr,
      $five: expr, $six: expr, $seven: expr, $eight: expr, $nine: expr) => {
-        Deck { card_counts: [$ten, $ace, $two, $three, $four, $five, $six, $seven, $eight, $nine] }
+        Deck::new([$ten, $ace, $two, $three, $four, $five, $six, $seven, $eight, $nine])
     };
 }
 
@@ -64,7 +221,43 @@ macro_rules! deck {
 #[macro_export]
 macro_rules! shoe {
     ($decks:expr) => {
-        Deck { card_counts: [16*$decks, 4*$decks, 4*$decks, 4*$decks, 4*$decks,
-                             4*$decks, 4*$decks, 4*$decks, 4*$decks, 4*$decks] }
+        Deck::new([16*$decks, 4*$decks, 4*$decks, 4*$decks, 4*$decks,
+                   4*$decks, 4*$decks, 4*$decks, 4*$decks, 4*$decks])
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::shoe;
+    use crate::types::{A, T};
+
+    use super::*;
+
+    /// `zobrist` is maintained incrementally by `added`/`removed`/`draw`; it must always agree
+    /// with a from-scratch hash of the resulting `card_counts`.
+    #[test]
+    fn test_zobrist_stays_in_sync_with_card_counts() {
+        let deck: Deck = shoe!(2);
+        assert_eq!(deck.zobrist(), zobrist_hash(&deck.card_counts));
+
+        let added = deck.added(A);
+        assert_eq!(added.zobrist(), zobrist_hash(&added.card_counts));
+
+        let removed = added.removed(T);
+        assert_eq!(removed.zobrist(), zobrist_hash(&removed.card_counts));
+
+        let mut drawn = removed.clone();
+        drawn.draw(&mut rand::thread_rng());
+        assert_eq!(drawn.zobrist(), zobrist_hash(&drawn.card_counts));
+    }
+
+    /// Two decks with the same composition must hash identically regardless of how each was
+    /// built, since that's what lets `EvCache` treat them as the same key.
+    #[test]
+    fn test_equivalent_compositions_hash_the_same() {
+        let built_up = Deck::default().added(T).added(T).added(A);
+        let via_new = Deck::new([2, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(built_up, via_new);
+        assert_eq!(built_up.zobrist(), via_new.zobrist());
+    }
+}
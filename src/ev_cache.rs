@@ -0,0 +1,108 @@
+//! A concurrent memoization cache for [`perfect_strategy`](crate::perfect_strategy)'s EV
+//! recursion, keyed by [Deck]'s incrementally maintained Zobrist hash of its composition.
+//!
+//! The standard `#[memoize]` cache used elsewhere in this crate guards its `HashMap` with a
+//! single lock, which becomes a bottleneck once many threads are all hammering `ev` with the
+//! same handful of recurring deck compositions. Sharding the cache by a cheap, incrementally
+//! maintained hash lets independent compositions update in parallel.
+
+use std::sync::Mutex;
+
+use enum_map::EnumMap;
+use memoize::lazy_static::lazy_static;
+
+use crate::deck::Deck;
+use crate::hand::canonical_hand::CanonicalHand;
+use crate::perfect_strategy::EvCalcResult;
+use crate::types::{Action, Rank};
+
+const NUM_SHARDS: usize = 64;
+
+/// The non-deck portion of an `ev` call's arguments, stored alongside the full `Deck` so a cache
+/// hit can be verified against a genuine composition match rather than trusting the hash alone.
+type EvCacheEntry = (EnumMap<Action, bool>, CanonicalHand, u32, Rank, Deck, EvCalcResult);
+
+/// A sharded, hash-bucketed memoization cache from `ev`'s arguments to its result. Sharding (one
+/// `Mutex` per bucket of hashes, rather than one global lock) lets threads working on distinct
+/// deck compositions make progress concurrently.
+pub struct EvCache {
+    shards: Vec<Mutex<std::collections::HashMap<u64, Vec<EvCacheEntry>>>>,
+}
+
+impl EvCache {
+    fn new() -> Self {
+        EvCache {
+            shards: (0..NUM_SHARDS).map(|_| Mutex::new(std::collections::HashMap::new())).collect(),
+        }
+    }
+
+    fn shard_for(&self, hash: u64) -> &Mutex<std::collections::HashMap<u64, Vec<EvCacheEntry>>> {
+        &self.shards[(hash as usize) % NUM_SHARDS]
+    }
+
+    pub fn get(
+        &self,
+        allowed_actions: EnumMap<Action, bool>,
+        player_hand: CanonicalHand,
+        splits_allowed: u32,
+        upcard: Rank,
+        deck: &Deck,
+    ) -> Option<EvCalcResult> {
+        let hash = deck.zobrist();
+        let shard = self.shard_for(hash).lock().unwrap();
+        let bucket = shard.get(&hash)?;
+        bucket.iter()
+            .find(|(a, h, s, u, d, _)| *a == allowed_actions && *h == player_hand && *s == splits_allowed && *u == upcard && d == deck)
+            .map(|(.., result)| result.clone())
+    }
+
+    pub fn insert(
+        &self,
+        allowed_actions: EnumMap<Action, bool>,
+        player_hand: CanonicalHand,
+        splits_allowed: u32,
+        upcard: Rank,
+        deck: Deck,
+        result: EvCalcResult,
+    ) {
+        let hash = deck.zobrist();
+        let mut shard = self.shard_for(hash).lock().unwrap();
+        shard.entry(hash).or_default().push((allowed_actions, player_hand, splits_allowed, upcard, deck, result));
+    }
+}
+
+lazy_static! {
+    pub static ref EV_CACHE: EvCache = EvCache::new();
+}
+
+#[cfg(test)]
+mod tests {
+    use enum_map::enum_map;
+
+    use crate::deck;
+    use crate::hand::canonical_hand::CanonicalHand;
+    use crate::perfect_strategy::{EvCalcResult, PayoffDistribution};
+    use crate::types::{Action, T};
+
+    use super::*;
+
+    /// {5, 6} and {4, 7} are distinct compositions but the same `CanonicalHand::Hard2Card(11)`, so
+    /// a result cached for one must be returned for the other.
+    #[test]
+    fn test_get_collapses_equivalent_compositions() {
+        let cache = EvCache::new();
+        let allowed_actions = enum_map! { _ => true };
+        let five_six = CanonicalHand::Empty + 5 + 6;
+        let four_seven = CanonicalHand::Empty + 4 + 7;
+        assert_eq!(five_six, CanonicalHand::Hard2Card(11));
+        let deck = deck![16, 4, 4, 4, 4, 4, 4, 4, 4, 4];
+        let result = EvCalcResult {
+            ev: 0.5, action: Action::Double, choices: EnumMap::default(),
+            distribution: PayoffDistribution::new(), insurance: None,
+        };
+
+        cache.insert(allowed_actions, five_six, 4, T, deck.clone(), result.clone());
+
+        assert_eq!(cache.get(allowed_actions, four_seven, 4, T, &deck), Some(result));
+    }
+}
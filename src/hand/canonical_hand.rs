@@ -6,7 +6,7 @@ use crate::types::{A, additive_value, Rank, T};
 /// A Canonical Hand is a summarization of a player's set of cards. All instances of a Canonical
 /// Hand must have identical strategy probabilities when given the same external context (dealer
 /// upcard, deck composition, etc.).
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize)]
 pub enum CanonicalHand {
     /// A zero-card hand.
     Empty,
@@ -1,8 +1,11 @@
 use std::{thread, time};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use derive_more::{Add, AddAssign};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use crate::basic_strategy::BasicStrategyChart;
 use crate::deck::Deck;
@@ -12,10 +15,16 @@ use crate::strategy_comparison::{BasicPerfectComparison, COMPARISON_CHART};
 
 mod basic_strategy;
 mod composition_strategy;
+mod counting;
 mod deck;
+mod ev_cache;
 mod hand;
+mod optimizer;
 mod perfect_strategy;
+mod replay;
 mod rules;
+mod settlement;
+mod side_bets;
 mod simulation;
 mod statistics;
 mod strategy_comparison;
@@ -24,6 +33,11 @@ mod types;
 const THREADS: u32 = 20;
 const TIME_BETWEEN_THREAD_REPORTS: Duration = Duration::from_millis(500);
 
+/// Stop simulating once the 95% confidence interval on the house edge is tighter than this (e.g.
+/// 0.0001 = "know the edge to within ±0.01%"). Set to `f64::NEG_INFINITY` to disable and run
+/// forever.
+const TARGET_EDGE_CI_HALF_WIDTH: f64 = f64::NEG_INFINITY;
+
 pub static RULES: BlackjackRules = RULES_1D_H17_NDAS_D10;
 
 #[derive(Default, Add, AddAssign)]
@@ -35,15 +49,27 @@ struct ComparisonResult {
 fn main() {
     let bs_chart = BasicStrategyChart::builtin(&RULES).unwrap();
 
+    // Logged so a surprising run can be replayed card-for-card: each thread's RNG is seeded
+    // deterministically from this value, so rerunning with the same seed (passed as the first
+    // command-line argument) reproduces every shoe. A random seed is drawn when none is given.
+    let seed: u64 = match std::env::args().nth(1) {
+        Some(arg) => arg.parse().expect("The seed argument must be an unsigned integer"),
+        None => rand::thread_rng().gen(),
+    };
+    println!("RNG seed: {} (rerun with this seed to reproduce this run)", seed);
+
     let status = Arc::new(Mutex::new(ComparisonResult::default()));
+    let stop = Arc::new(AtomicBool::new(false));
     let mut thread_handles = vec![];
 
-    for _ in 0..THREADS {
+    for thread_idx in 0..THREADS {
         let strategy_chart_this_thread = bs_chart.clone();
         let status_clone = status.clone();
+        let stop_clone = stop.clone();
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(thread_idx as u64));
         thread_handles.push(thread::spawn(move || {
-            loop {
-                play_hands_compare_and_report(&strategy_chart_this_thread, &status_clone)
+            while !stop_clone.load(Ordering::Relaxed) {
+                play_hands_compare_and_report(&strategy_chart_this_thread, &mut rng, &status_clone, &stop_clone)
             }
         }));
     }
@@ -52,43 +78,58 @@ fn main() {
 
     let start_time = Instant::now();
     let mut times_printed: u64 = 0;
-    let mut hands_played_last_seen: u64 = 0;
+    let mut hands_started_last_seen: u64 = 0;
     let mut shoes_played_last_seen: u64 = 0;
     loop {
         thread::sleep(time::Duration::from_secs(1));
         let s = status.lock().unwrap();
-        println!("Played {} hands ({} shoes) and had total of {:+} returned. Edge = {}%, {} hands/sec total ({} hands/{} shoes in last second), {}/{} deviant actions {}% average +EV/hand",
-                 s.sim.hands_played, s.sim.shoes_played,
-                 s.sim.roi, s.sim.roi / s.sim.hands_played as f64 * 100f64,
-                 (s.sim.hands_played as f64 / start_time.elapsed().as_secs_f64()).round(),
-                 (s.sim.hands_played - hands_played_last_seen),
+        let edge = s.sim.roi_stats.mean();
+        let edge_ci_half_width = s.sim.roi_stats.ci_95_half_width();
+        println!("Played {} hands ({} shoes) and had total of {:+} returned. Edge = {}% (±{}%, 95% CI), {} hands/sec total ({} hands/{} shoes in last second), {}/{} deviant actions {}% average +EV/hand",
+                 s.sim.hands_started, s.sim.shoes_played,
+                 s.sim.roi, edge * 100f64, edge_ci_half_width * 100f64,
+                 (s.sim.hands_started as f64 / start_time.elapsed().as_secs_f64()).round(),
+                 (s.sim.hands_started - hands_started_last_seen),
                  (s.sim.shoes_played - shoes_played_last_seen),
                  s.comparison.deviations, s.sim.decisions_made,
-                 s.comparison.gained_ev / s.sim.hands_played as f64 * 100f64,
+                 s.comparison.gained_ev / s.sim.hands_started as f64 * 100f64,
         );
 
-        hands_played_last_seen = s.sim.hands_played;
+        hands_started_last_seen = s.sim.hands_started;
         shoes_played_last_seen = s.sim.shoes_played;
 
+        if edge_ci_half_width < TARGET_EDGE_CI_HALF_WIDTH {
+            println!("House edge known to within the target precision; stopping.");
+            stop.store(true, Ordering::Relaxed);
+            drop(s);
+            break;
+        }
+
         times_printed += 1;
         if times_printed % 10 == 0 {
             println!("{}", COMPARISON_CHART.lock().unwrap())
         }
     }
+
+    for handle in thread_handles {
+        handle.join().unwrap();
+    }
 }
 
 fn play_hands_compare_and_report(
     strategy_chart: &BasicStrategyChart,
-    status: &Arc<Mutex<ComparisonResult>>
+    rng: &mut impl Rng,
+    status: &Arc<Mutex<ComparisonResult>>,
+    stop: &Arc<AtomicBool>,
 ) {
     let mut result_accum = ComparisonResult::default();
 
     let start_time = Instant::now();
-    while start_time.elapsed() < TIME_BETWEEN_THREAD_REPORTS {
+    while start_time.elapsed() < TIME_BETWEEN_THREAD_REPORTS && !stop.load(Ordering::Relaxed) {
         let mut deck = shoe!(RULES.decks);
         while deck.len() > RULES.shuffle_at_cards {
             let (sim, cmp) = play_hand(
-                &mut deck, PlayerDecisionMethod::BasicPerfectComparison(strategy_chart),
+                &mut deck, rng, PlayerDecisionMethod::BasicPerfectComparison(strategy_chart),
             );
             result_accum.sim += sim;
             result_accum.comparison += cmp;
@@ -0,0 +1,221 @@
+//! Simulated-annealing search over a counting strategy's bet ramp and index-play thresholds,
+//! using the simulator itself as the objective function. Hand-tuning a spread and a deviation
+//! table against a specific rule set and penetration is tedious; this lets the crate search for
+//! one instead.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::basic_strategy::BasicStrategyChart;
+use crate::counting::{BetRamp, CountingPlay, CountingSystem, DeviationTable};
+use crate::rules::BlackjackRules;
+use crate::shoe;
+use crate::simulation::{play_hand, PlayerDecisionMethod};
+use crate::statistics::RoiAccumulator;
+
+/// Worker threads used to evaluate a single candidate's batch of simulated hands.
+const EVAL_THREADS: u32 = 8;
+
+/// The tunable parameters of a counting strategy: the bet ramp's `(true_count, bet_units)` steps
+/// and each index play's count threshold, in the same order as the [DeviationTable] it was derived
+/// from.
+#[derive(Clone, Debug)]
+pub struct StrategyParams {
+    pub bet_ramp_steps: Vec<(f64, f64)>,
+    pub deviation_thresholds: Vec<f64>,
+}
+
+impl StrategyParams {
+    pub fn from_strategy(bet_ramp: &BetRamp, deviations: &DeviationTable) -> Self {
+        StrategyParams {
+            bet_ramp_steps: bet_ramp.steps.clone(),
+            deviation_thresholds: deviations.plays.iter().map(|play| play.threshold).collect(),
+        }
+    }
+
+    fn bet_ramp(&self) -> BetRamp {
+        BetRamp::new(self.bet_ramp_steps.clone())
+    }
+
+    fn deviations(&self, base: &DeviationTable) -> DeviationTable {
+        let mut deviations = base.clone();
+        for (play, &threshold) in deviations.plays.iter_mut().zip(self.deviation_thresholds.iter()) {
+            play.threshold = threshold;
+        }
+        deviations
+    }
+
+    /// A neighboring parameter vector: one randomly-chosen bet size or deviation threshold,
+    /// nudged by a uniform random amount in `[-step_size, step_size]`. Bet sizes are floored at 1
+    /// unit, so a downward nudge of a 1-unit bet leaves the vector unchanged.
+    fn neighbor(&self, step_size: f64, rng: &mut impl Rng) -> StrategyParams {
+        let mut next = self.clone();
+        let num_params = next.bet_ramp_steps.len() + next.deviation_thresholds.len();
+        let idx = rng.gen_range(0..num_params);
+        let delta = rng.gen_range(-step_size..=step_size);
+
+        if idx < next.bet_ramp_steps.len() {
+            // Bets can't drop below 1 unit, but the threshold at which a bet size kicks in
+            // is left alone here; only the size itself is tuned.
+            next.bet_ramp_steps[idx].1 = (next.bet_ramp_steps[idx].1 + delta).max(1.0);
+        } else {
+            next.deviation_thresholds[idx - next.bet_ramp_steps.len()] += delta;
+        }
+
+        next
+    }
+}
+
+/// The outcome of an [anneal] run: the best parameters found and their estimated player edge
+/// (ROI per hand, so negative means a house edge).
+pub struct OptimizationResult {
+    pub params: StrategyParams,
+    pub edge_estimate: f64,
+}
+
+/// Anneal `initial`'s bet ramp and deviation thresholds against the simulator for `time_budget`.
+/// Each candidate is scored by playing about `hands_per_batch` hands (split across [EVAL_THREADS]
+/// worker threads, see [evaluate]) and reading back the
+/// resulting mean ROI. Worsening candidates are still accepted with probability
+/// `exp(-delta / temperature)`, with `temperature` cooling geometrically from
+/// `start_temperature` to a small floor over the time budget, so the search can escape local
+/// optima early on and settles down as the budget runs out.
+///
+/// # Arguments
+/// * `seed` - Seeds every random choice made during the search (candidate neighbors and each
+///            [evaluate] batch's shoes), so rerunning with the same seed reproduces the same run.
+pub fn anneal(
+    rules: &BlackjackRules,
+    chart: &BasicStrategyChart,
+    system: Arc<dyn CountingSystem>,
+    base_deviations: &DeviationTable,
+    initial: StrategyParams,
+    hands_per_batch: u64,
+    start_temperature: f64,
+    step_size: f64,
+    time_budget: Duration,
+    seed: u64,
+) -> OptimizationResult {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut current = initial;
+    let mut current_edge = evaluate(rules, chart, &system, base_deviations, &current, hands_per_batch, rng.gen());
+    let mut best = current.clone();
+    let mut best_edge = current_edge;
+
+    let end_temperature = start_temperature * 1e-3;
+    let start = Instant::now();
+    while start.elapsed() < time_budget {
+        let progress = start.elapsed().as_secs_f64() / time_budget.as_secs_f64();
+        let temperature = start_temperature * (end_temperature / start_temperature).powf(progress);
+
+        let candidate = current.neighbor(step_size, &mut rng);
+        let candidate_edge = evaluate(rules, chart, &system, base_deviations, &candidate, hands_per_batch, rng.gen());
+
+        // Higher ROI is better; a positive delta means the candidate made things worse.
+        let delta = current_edge - candidate_edge;
+        if delta <= 0.0 || rng.gen::<f64>() < (-delta / temperature).exp() {
+            current = candidate;
+            current_edge = candidate_edge;
+            if current_edge > best_edge {
+                best = current.clone();
+                best_edge = current_edge;
+            }
+        }
+    }
+
+    OptimizationResult { params: best, edge_estimate: best_edge }
+}
+
+/// Play `hands_per_batch` hands under `params` (split across [EVAL_THREADS] threads, each with its
+/// own shoe, seeded deterministically from `seed`) and return the resulting mean ROI per hand.
+/// Each thread plays `hands_per_batch / EVAL_THREADS` hands (at least 1), so the total is rounded
+/// down to a multiple of [EVAL_THREADS].
+fn evaluate(
+    rules: &BlackjackRules,
+    chart: &BasicStrategyChart,
+    system: &Arc<dyn CountingSystem>,
+    base_deviations: &DeviationTable,
+    params: &StrategyParams,
+    hands_per_batch: u64,
+    seed: u64,
+) -> f64 {
+    let bet_ramp = params.bet_ramp();
+    let deviations = params.deviations(base_deviations);
+    let hands_per_thread = (hands_per_batch / EVAL_THREADS as u64).max(1);
+
+    let handles: Vec<_> = (0..EVAL_THREADS).map(|thread_idx| {
+        let chart = chart.clone();
+        let bet_ramp = bet_ramp.clone();
+        let deviations = deviations.clone();
+        let system = system.clone();
+        let rules = rules.clone();
+
+        thread::spawn(move || {
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(thread_idx as u64));
+            let mut roi_stats = RoiAccumulator::default();
+            let mut deck = shoe!(rules.decks);
+            for _ in 0..hands_per_thread {
+                if deck.len() <= rules.shuffle_at_cards {
+                    deck = shoe!(rules.decks);
+                }
+                let counting_play = CountingPlay {
+                    chart: &chart,
+                    deviations: &deviations,
+                    system: system.as_ref(),
+                    bet_ramp: &bet_ramp,
+                    num_decks: rules.decks,
+                };
+                let (result, _) = play_hand(&mut deck, &mut rng, PlayerDecisionMethod::CountingStrategy(&counting_play));
+                roi_stats += result.roi_stats;
+            }
+            roi_stats
+        })
+    }).collect();
+
+    let mut total = RoiAccumulator::default();
+    for handle in handles {
+        total += handle.join().unwrap();
+    }
+    total.mean()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::counting::{BetRamp, DeviationTable};
+
+    use super::StrategyParams;
+
+    #[test]
+    fn test_from_strategy_round_trips_parameter_count() {
+        let bet_ramp = BetRamp::new(vec![(f64::NEG_INFINITY, 1.0), (2.0, 4.0), (4.0, 8.0)]);
+        let deviations = DeviationTable::classic_hi_lo();
+        let params = StrategyParams::from_strategy(&bet_ramp, &deviations);
+
+        assert_eq!(params.bet_ramp_steps.len(), 3);
+        assert_eq!(params.deviation_thresholds.len(), deviations.plays.len());
+    }
+
+    #[test]
+    fn test_neighbor_perturbs_exactly_one_parameter() {
+        // The base bet sits above the 1-unit floor `neighbor` clamps to, so a downward nudge of
+        // it still registers as a change.
+        let bet_ramp = BetRamp::new(vec![(f64::NEG_INFINITY, 2.0), (2.0, 4.0)]);
+        let deviations = DeviationTable::classic_hi_lo();
+        let params = StrategyParams::from_strategy(&bet_ramp, &deviations);
+
+        let mut rng = rand::thread_rng();
+        let next = params.neighbor(0.5, &mut rng);
+
+        let changed_bets = params.bet_ramp_steps.iter().zip(next.bet_ramp_steps.iter())
+            .filter(|(a, b)| a.1 != b.1).count();
+        let changed_deviations = params.deviation_thresholds.iter().zip(next.deviation_thresholds.iter())
+            .filter(|(a, b)| a != b).count();
+
+        assert_eq!(changed_bets + changed_deviations, 1);
+    }
+}
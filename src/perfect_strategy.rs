@@ -1,17 +1,20 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
-use enum_map::EnumMap;
+use enum_map::{enum_map, EnumMap};
 use memoize::memoize;
+use ordered_float::OrderedFloat;
 use strum::EnumCount;
 
 use crate::deck::Deck;
+use crate::ev_cache::EV_CACHE;
 use crate::hand::canonical_hand::CanonicalHand;
-use crate::hand::canonical_hand::CanonicalHand::Busted;
+use crate::hand::canonical_hand::CanonicalHand::{Blackjack, Busted};
 use crate::hand::total_hashed::{TotalHashedDealerHand};
 use crate::RULES;
 use crate::types::{*};
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct EvCalcResult {
     pub ev: f64,
     pub action: Action,
@@ -19,6 +22,99 @@ pub struct EvCalcResult {
     /// The EV of each possible action in a situation. If an action is not allowed, the EV will
     /// be returned as `f64::NEG_INFINITY`.
     pub choices: EnumMap<Action, f64>,
+
+    /// The full discrete payoff distribution for `action`, the line actually taken. `ev` is
+    /// always `ev_of(&distribution)`; kept as its own field since most callers only want the
+    /// scalar and recomputing it from the distribution on every read would be wasted work.
+    pub distribution: PayoffDistribution,
+
+    /// The recommended insurance/even-money take-or-decline, when the dealer's upcard makes
+    /// insurance available. `None` when insurance isn't being offered.
+    pub insurance: Option<InsuranceDecision>,
+}
+
+/// A discrete probability distribution over net payoffs, in multiples of the original bet (`-1`
+/// for a flat loss, `+1.5` for a natural Blackjack, `+2` for a won double, etc.), keyed by
+/// [`OrderedFloat`] since `f64` alone isn't `Ord`. Probabilities sum to `1.0`.
+pub type PayoffDistribution = BTreeMap<OrderedFloat<f64>, f64>;
+
+/// The mean of a payoff distribution: `sum(payoff * probability)`. This is exactly
+/// [`EvCalcResult::ev`] for the chosen action - see that field's doc comment.
+pub fn ev_of(distribution: &PayoffDistribution) -> f64 {
+    distribution.iter().map(|(payoff, &p)| payoff.0 * p).sum()
+}
+
+/// The variance of a payoff distribution around its own mean.
+pub fn variance_of(distribution: &PayoffDistribution) -> f64 {
+    let mean = ev_of(distribution);
+    distribution.iter().map(|(payoff, &p)| p * (payoff.0 - mean).powi(2)).sum()
+}
+
+/// The standard deviation of a payoff distribution - the per-hand risk figure that, along with
+/// `ev`, feeds risk-of-ruin and Kelly bet-sizing calculations.
+pub fn std_dev_of(distribution: &PayoffDistribution) -> f64 {
+    variance_of(distribution).sqrt()
+}
+
+/// A distribution that always pays off `payoff`, with probability 1.
+fn point_mass(payoff: f64) -> PayoffDistribution {
+    let mut distribution = PayoffDistribution::new();
+    distribution.insert(OrderedFloat(payoff), 1f64);
+    distribution
+}
+
+/// Accumulate `probability` onto whatever mass `distribution` already has at `payoff`. A no-op
+/// for non-positive probabilities, since every `p_next_card_is_each`-style loop in this file
+/// already skips zero-probability branches by continuing past them.
+fn add_into(distribution: &mut PayoffDistribution, payoff: f64, probability: f64) {
+    if probability <= 0f64 {
+        return;
+    }
+    *distribution.entry(OrderedFloat(payoff)).or_insert(0f64) += probability;
+}
+
+/// Multiply every payoff key by `factor`, leaving probabilities untouched - doubling a hand
+/// doubles every payoff it could have reached, but doesn't change how likely each one is.
+fn scale_payoffs(distribution: &PayoffDistribution, factor: f64) -> PayoffDistribution {
+    distribution.iter().map(|(payoff, &p)| (OrderedFloat(payoff.0 * factor), p)).collect()
+}
+
+/// The distribution of `a`'s payoff plus `b`'s payoff, assuming the two are independent - used to
+/// combine a split's two sub-hands, whose combined wager result is the sum of each hand's own.
+fn convolve(a: &PayoffDistribution, b: &PayoffDistribution) -> PayoffDistribution {
+    let mut out = PayoffDistribution::new();
+    for (a_payoff, &a_p) in a {
+        for (b_payoff, &b_p) in b {
+            add_into(&mut out, a_payoff.0 + b_payoff.0, a_p * b_p);
+        }
+    }
+    out
+}
+
+/// The distributional form of the `p_dealer_natural` wager-size correction (see that function's
+/// doc comment): under ENHC, a dealer natural only reclaims the player's original 1-unit stake,
+/// not the full doubled/split wager, so `p_natural` worth of probability mass moves from the `-2`
+/// payoff to `-1`. This shifts the mean by exactly `+p_natural`, matching the scalar correction
+/// term added in `ev_split_approx`/`ev_split_exact`. Like that scalar correction, this
+/// is an approximation rather than a fully re-derived conditional distribution: it assumes `-2` is
+/// the payoff a dealer natural would otherwise have landed on, without reconstructing which of the
+/// underlying branches that mass actually came from.
+fn apply_dealer_natural_correction(distribution: &PayoffDistribution, p_natural: f64) -> PayoffDistribution {
+    if p_natural <= 0f64 {
+        return distribution.clone();
+    }
+    let mut out = distribution.clone();
+    *out.entry(OrderedFloat(-2f64)).or_insert(0f64) -= p_natural;
+    add_into(&mut out, -1f64, p_natural);
+    out
+}
+
+/// Whether taking insurance (or even money on a player Blackjack) is the better play, and the EV
+/// of doing so. See [`insurance_ev`].
+#[derive(PartialEq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct InsuranceDecision {
+    pub take: bool,
+    pub ev: f64,
 }
 
 /// Perform a combinatorial analysis on the current hand and draw pile to calculate the optimal
@@ -38,7 +134,70 @@ pub fn perfect_play(
     dealer_up: Rank,
     deck: &Deck
 ) -> EvCalcResult {
-    ev(allowed_actions, CanonicalHand::from(hand.clone()), splits_allowed, dealer_up, *deck)
+    let mut result = ev(allowed_actions, CanonicalHand::from(hand.clone()), splits_allowed, dealer_up, deck.clone());
+    if dealer_up == A {
+        let ev = insurance_ev(deck);
+        result.insurance = Some(InsuranceDecision { take: ev > 0f64, ev });
+    }
+    result
+}
+
+/// The EV of taking insurance (or even money on a player Blackjack), computed directly from the
+/// deck's current composition rather than `dealer_probabilities_beating`'s post-peek assumption,
+/// which has already ruled out the dealer having Blackjack at all. Insurance/even money pays 2:1,
+/// so with `p` = P(the Dealer's hole card is a Ten), the EV is `p * 2 - 1`.
+///
+/// # Arguments
+/// * `deck` - The remaining draw pile, INCLUDING the Dealer's unknown down card.
+pub fn insurance_ev(deck: &Deck) -> f64 {
+    let p_ten_in_hole = deck[T] as f64 / deck.len() as f64;
+    p_ten_in_hole * 2f64 - 1f64
+}
+
+/// The probability the dealer's hidden second card completes a natural Blackjack, for rule sets
+/// where the dealer doesn't peek (see [`BlackjackRules::dealer_peeks`]) and so can still draw one
+/// after the player has already doubled or split. Zero whenever the dealer peeks (the peek would
+/// have already settled the hand before the player could act) or the upcard can't complete a
+/// natural at all.
+///
+/// # Arguments
+/// * `upcard` - The dealer's showing card.
+/// * `deck` - The remaining draw pile, INCLUDING the Dealer's unknown down card.
+fn p_dealer_natural(upcard: Rank, deck: &Deck) -> f64 {
+    if RULES.dealer_peeks {
+        return 0f64;
+    }
+
+    p_dealer_natural_unconditioned(upcard, deck)
+}
+
+/// [`p_dealer_natural`]'s raw probability, without the `RULES.dealer_peeks` gate - for the one
+/// decision point (early surrender) that is itself offered *before* the dealer's peek, so the risk
+/// of a dealer natural applies regardless of whether this rule set peeks at all.
+///
+/// # Arguments
+/// * `upcard` - The dealer's showing card.
+/// * `deck` - The remaining draw pile, INCLUDING the Dealer's unknown down card.
+pub(crate) fn p_dealer_natural_unconditioned(upcard: Rank, deck: &Deck) -> f64 {
+    match upcard {
+        A => deck[T] as f64 / deck.len() as f64,
+        T => deck[A] as f64 / deck.len() as f64,
+        _ => 0f64,
+    }
+}
+
+/// [`p_dealer_natural`], averaged over every card the double/split in progress could still deal
+/// from `deck`, weighted by that card's own probability - the same `p_next_card_is_each` loop
+/// [`distribution_hit`] and the split-descendant functions already walk for the real draw.
+/// Removing a card shifts the remaining Ten/Ace frequency the dealer's hole card is drawn from, so
+/// pricing the correction against the stale pre-draw `deck` would be inconsistent with the `-2`
+/// mass it's adjusting (worst in single-deck games).
+fn p_dealer_natural_after_draw(upcard: Rank, deck: &Deck) -> f64 {
+    let p_next_card_is = p_next_card_is_each(deck, true, true);
+    RANKS
+        .filter(|&next_card| p_next_card_is[next_card] > 0f64)
+        .map(|next_card| p_next_card_is[next_card] * p_dealer_natural(upcard, &deck.removed(next_card)))
+        .sum()
 }
 
 /// Analyze the current deck to calculate the EV of taking an insurance bet. This function assumes
@@ -58,13 +217,108 @@ pub fn perfect_insure(deck: &Deck) -> (bool, f64) {
     (ev > 0.0, ev)
 }
 
-#[memoize(Capacity: 1_000_000)]
+/// The optimal action and per-action EVs for one reachable starting hand against one dealer
+/// upcard, as computed by [`strategy_chart`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StrategyChartEntry {
+    pub hand: CanonicalHand,
+    pub dealer_up: Rank,
+    pub result: EvCalcResult,
+}
+
+/// A full sweep of [`perfect_play`] over every reachable 2-card starting hand and dealer upcard
+/// for a single deck composition, suitable for dumping to disk as JSON and diffing against a
+/// chart computed under different rules or deck compositions instead of re-running the
+/// recursion.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StrategyChart {
+    /// The [`crate::RULES`] in effect when this chart was computed, as rendered by
+    /// `BlackjackRules`'s `Display` impl, so a chart's on-disk format is self-describing.
+    pub rules: String,
+    pub entries: Vec<StrategyChartEntry>,
+}
+
+impl StrategyChart {
+    /// Serialize this chart to a JSON string.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// Every distinct 2-card starting hand reachable by dealing two cards of unspecified suit, in a
+/// deterministic (first card, second card) order, excluding natural Blackjack (which is settled
+/// immediately and never reaches a player decision).
+fn all_initial_hands() -> Vec<CanonicalHand> {
+    let mut hands = vec![];
+    for first in RANKS {
+        for second in RANKS {
+            let hand = CanonicalHand::Empty + first + second;
+            if hand != Blackjack && !hands.contains(&hand) {
+                hands.push(hand);
+            }
+        }
+    }
+    hands
+}
+
+/// Sweep every reachable 2-card starting hand against all ten dealer upcards and calculate the
+/// optimal action and per-action EVs for each, as though this were the player's first decision
+/// point with a single hand in play.
+///
+/// # Arguments
+/// * `deck` - The deck composition to sweep against, INCLUDING the dealer's unknown down card.
+pub fn strategy_chart(deck: &Deck) -> StrategyChart {
+    let mut entries = vec![];
+
+    for hand in all_initial_hands() {
+        for dealer_up in RANKS {
+            let mut splits_allowed = 0;
+            let allowed_actions = enum_map! {
+                Action::Stand => true,
+                Action::Hit => true,
+                Action::Double => RULES.double_policy.allows(hand.total()),
+                Action::Split => match hand {
+                    CanonicalHand::Pair(A) => { splits_allowed = RULES.split_aces_limit - 1; splits_allowed > 0 },
+                    CanonicalHand::Pair(_) => { splits_allowed = RULES.split_hands_limit - 1; splits_allowed > 0 },
+                    _ => false,
+                },
+                Action::Surrender => RULES.late_surrender && can_surrender(&hand, 1),
+            };
+
+            let result = perfect_play(allowed_actions, &hand, splits_allowed, dealer_up, deck);
+            entries.push(StrategyChartEntry { hand, dealer_up, result });
+        }
+    }
+
+    StrategyChart { rules: RULES.to_string(), entries }
+}
+
+/// Combinatorial EV recursion, memoized by an incremental Zobrist hash of `deck`'s composition
+/// (see [`crate::ev_cache`]) rather than the generic `#[memoize]` HashMap used elsewhere, since
+/// this is by far the hottest recursion in the crate and recurring compositions are extremely
+/// common across millions of hands.
 fn ev(
     allowed_actions: EnumMap<Action, bool>,
     player_hand: CanonicalHand,
     splits_allowed: u32,
     upcard: Rank,
     deck: Deck
+) -> EvCalcResult {
+    if let Some(cached) = EV_CACHE.get(allowed_actions, player_hand, splits_allowed, upcard, &deck) {
+        return cached;
+    }
+
+    let result = ev_uncached(allowed_actions, player_hand, splits_allowed, upcard, deck.clone());
+    EV_CACHE.insert(allowed_actions, player_hand, splits_allowed, upcard, deck, result.clone());
+    result
+}
+
+fn ev_uncached(
+    allowed_actions: EnumMap<Action, bool>,
+    player_hand: CanonicalHand,
+    splits_allowed: u32,
+    upcard: Rank,
+    deck: Deck
 ) -> EvCalcResult {
     // Split not in allowed_actions implies splits_allowed == 0 and vice versa
     assert!(allowed_actions[Action::Split] ^ (splits_allowed == 0));
@@ -73,27 +327,30 @@ fn ev(
 
     if player_hand == Busted {
         choices[Action::Stand] = -1f64;
-        return EvCalcResult { ev: -1f64, action: Action::Stand, choices };
+        return EvCalcResult { ev: -1f64, action: Action::Stand, choices, distribution: point_mass(-1f64), insurance: None };
     }
 
+    let mut distributions: EnumMap<Action, PayoffDistribution> = EnumMap::default();
+
     for (allowed_action, _) in allowed_actions.iter().filter(|(_, &allowed)| allowed) {
-        match allowed_action {
-            Action::Stand => {
-                choices[Action::Stand] = ev_stand(player_hand, upcard, deck);
-            }
+        let distribution = match allowed_action {
+            Action::Stand => distribution_stand(player_hand, upcard, deck.clone()),
 
-            Action::Hit => {
-                choices[Action::Hit] = ev_hit(player_hand, upcard, deck, true);
-            }
+            Action::Hit => distribution_hit(player_hand, upcard, deck.clone(), true),
 
-            Action::Double => {
-                choices[Action::Double] = ev_double(player_hand, upcard, deck);
-            }
+            Action::Double => distribution_double(player_hand, upcard, deck.clone()),
 
-            Action::Split => {
-                choices[Action::Split] = ev_split(player_hand, splits_allowed, upcard, deck);
-            }
-        }
+            Action::Split => if RULES.exact_split_resolution {
+                distribution_split_exact(player_hand, splits_allowed, upcard, deck.clone())
+            } else {
+                distribution_split_approx(player_hand, splits_allowed, upcard, deck.clone())
+            },
+
+            Action::Surrender => distribution_surrender(),
+        };
+
+        choices[allowed_action] = ev_of(&distribution);
+        distributions[allowed_action] = distribution;
     }
 
     // Return the choice that maximizes expected value.
@@ -103,12 +360,15 @@ fn ev(
             max_ev_choice = action;
         }
     }
-    EvCalcResult { ev: choices[max_ev_choice], action: max_ev_choice, choices }
+    let distribution = std::mem::take(&mut distributions[max_ev_choice]);
+    EvCalcResult { ev: choices[max_ev_choice], action: max_ev_choice, choices, distribution, insurance: None }
 }
 
-fn ev_stand(player_hand: CanonicalHand, upcard: Rank, deck: Deck) -> f64 {
+/// The three-point payoff distribution of standing: win the bet, push, or lose it, driven by
+/// [`dealer_probabilities_beating`].
+fn distribution_stand(player_hand: CanonicalHand, upcard: Rank, deck: Deck) -> PayoffDistribution {
     if player_hand == Busted {
-        return -1f64;
+        return point_mass(-1f64);
     }
 
     let (p_dealer_win, p_push) = dealer_probabilities_beating(
@@ -116,13 +376,19 @@ fn ev_stand(player_hand: CanonicalHand, upcard: Rank, deck: Deck) -> f64 {
     );
     let p_player_win: f64 = 1f64 - p_dealer_win - p_push;
 
-    p_player_win - p_dealer_win
+    let mut distribution = PayoffDistribution::new();
+    add_into(&mut distribution, 1f64, p_player_win);
+    add_into(&mut distribution, 0f64, p_push);
+    add_into(&mut distribution, -1f64, p_dealer_win);
+    distribution
 }
 
-fn ev_hit(player_hand: CanonicalHand, upcard: Rank, deck: Deck, can_act_again: bool) -> f64 {
+/// The payoff distribution of hitting: a mix of every next-card branch's own distribution,
+/// weighted by `p_next_card_is[next_card]`.
+fn distribution_hit(player_hand: CanonicalHand, upcard: Rank, deck: Deck, can_act_again: bool) -> PayoffDistribution {
     // Base case - the player busted.
     if player_hand == Busted {
-        return -1f64;
+        return point_mass(-1f64);
     }
 
     // After hitting, only Stand and Hit are allowed
@@ -132,31 +398,107 @@ fn ev_hit(player_hand: CanonicalHand, upcard: Rank, deck: Deck, can_act_again: b
 
     // Recursive case - what can happen with the next card?
     let p_next_card_is = p_next_card_is_each(&deck, true, true);
-    let mut cumul_ev = 0f64;
+    let mut distribution = PayoffDistribution::new();
     for next_card in RANKS {
         if p_next_card_is[next_card] <= 0f64 {
             continue;
         }
 
         let deck_after_this_card = deck.removed(next_card);
-        if can_act_again {
-            cumul_ev += p_next_card_is[next_card] * ev(actions_allowed_after, player_hand + next_card, 0, upcard, deck_after_this_card).ev;
+        let child_distribution = if can_act_again {
+            ev(actions_allowed_after, player_hand + next_card, 0, upcard, deck_after_this_card).distribution
         } else {
-            cumul_ev += p_next_card_is[next_card] * ev_stand(player_hand + next_card, upcard, deck_after_this_card);
+            distribution_stand(player_hand + next_card, upcard, deck_after_this_card)
+        };
+        for (payoff, &p) in &child_distribution {
+            add_into(&mut distribution, payoff.0, p * p_next_card_is[next_card]);
         }
     }
 
-    cumul_ev
+    distribution
 }
 
-fn ev_double(player_hand: CanonicalHand, upcard: Rank, deck: Deck) -> f64 {
+fn distribution_double(player_hand: CanonicalHand, upcard: Rank, deck: Deck) -> PayoffDistribution {
     // Not recursive - only 1 card left.
-    2f64 * ev_hit(player_hand, upcard, deck, false)
+    let p_natural = p_dealer_natural_after_draw(upcard, &deck);
+    let one_card_distribution = distribution_hit(player_hand, upcard, deck, false);
+    let doubled = scale_payoffs(&one_card_distribution, 2f64);
+    apply_dealer_natural_correction(&doubled, p_natural)
 }
 
-fn ev_split(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck: Deck) -> f64 {
-    // This function returns the total EV of both split hands added together.
+/// EV of surrendering: always half the bet back, forfeited unconditionally regardless of deck or
+/// upcard.
+pub fn ev_surrender() -> f64 {
+    -0.5
+}
+
+/// The payoff distribution of surrendering: always exactly half the bet back.
+fn distribution_surrender() -> PayoffDistribution {
+    point_mass(-0.5)
+}
 
+/// Whether `hand` is eligible to surrender: it must be the player's initial two-card hand (no
+/// split or hit has happened yet).
+pub fn can_surrender(hand: &CanonicalHand, num_hands: u32) -> bool {
+    num_hands == 1 && matches!(hand, CanonicalHand::Hard2Card(_) | CanonicalHand::Soft2Card(_) | CanonicalHand::Pair(_))
+}
+
+/// The EV of a single hand formed by pairing `split_card` with a freshly drawn second card,
+/// exactly as happens immediately after a split, given `splits_allowed` further splits remain
+/// available to it.
+fn ev_split_descendant(split_card: Rank, splits_allowed: u32, upcard: Rank, deck: &Deck) -> f64 {
+    let can_act_after = RULES.hit_split_aces || split_card != A;
+    let mut actions_allowed_after = EnumMap::default();
+    actions_allowed_after[Action::Stand] = true;
+    actions_allowed_after[Action::Hit] = can_act_after;
+
+    let p_next_card_is = p_next_card_is_each(deck, true, true);
+    let mut cumul_ev = 0f64;
+    for new_second_card in RANKS {
+        if p_next_card_is[new_second_card] <= 0f64 {
+            continue;
+        }
+
+        actions_allowed_after[Action::Split] = splits_allowed > 1 && new_second_card == split_card;
+        let splits_allowed_after = if actions_allowed_after[Action::Split] { splits_allowed - 1 } else { 0 };
+
+        let deck_after_this_card = deck.removed(new_second_card);
+        let hand = CanonicalHand::Single(split_card) + new_second_card;
+        let hand_ev = if can_act_after {
+            ev(actions_allowed_after, hand, splits_allowed_after, upcard, deck_after_this_card).ev
+        } else {
+            ev_of(&distribution_stand(hand, upcard, deck_after_this_card))
+        };
+        cumul_ev += hand_ev * p_next_card_is[new_second_card];
+    }
+
+    cumul_ev
+}
+
+/// The fast approximation of a split's EV: evaluate both resulting hands independently against
+/// an identically-shaped deck and double. This double-counts the cards that one hand's draws
+/// would, in reality, have removed from the other's deck - see [`ev_split_exact`] for the
+/// composition-exact alternative, gated behind [`crate::rules::BlackjackRules::exact_split_resolution`]
+/// because it is far more expensive to compute.
+fn ev_split_approx(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck: Deck) -> f64 {
+    assert!(splits_allowed > 0);
+
+    let split_card = match player_hand {
+        CanonicalHand::Pair(r) => r,
+        _ => panic!("Tried to split a non-paired hand!")
+    };
+
+    // Under ENHC, a dealer natural only reclaims the player's original 1-unit wager, not both
+    // hands' worth of split bets, so add back the extra unit the per-hand EVs already charged
+    // against it.
+    2f64 * ev_split_descendant(split_card, splits_allowed, upcard, &deck) + p_dealer_natural_after_draw(upcard, &deck)
+}
+
+/// The composition-exact alternative to [`ev_split_approx`]: play the first split hand all the
+/// way to completion, then thread whichever deck it leaves behind (there may be many, each with
+/// its own probability - see [`deck_distribution_after`]) into the second hand's own draw and
+/// evaluation, instead of assuming both hands draw from identically-shaped decks.
+fn ev_split_exact(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck: Deck) -> f64 {
     assert!(splits_allowed > 0);
 
     let split_card = match player_hand {
@@ -169,9 +511,49 @@ fn ev_split(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck:
     actions_allowed_after[Action::Stand] = true;
     actions_allowed_after[Action::Hit] = can_act_after;
 
-    // Recursive case - what can happen with the new second card?
     let p_next_card_is = p_next_card_is_each(&deck, true, true);
     let mut cumul_ev = 0f64;
+    for first_hand_second_card in RANKS {
+        if p_next_card_is[first_hand_second_card] <= 0f64 {
+            continue;
+        }
+
+        actions_allowed_after[Action::Split] = splits_allowed > 1 && first_hand_second_card == split_card;
+        let splits_allowed_after = if actions_allowed_after[Action::Split] { splits_allowed - 1 } else { 0 };
+
+        let deck_after_first_card = deck.removed(first_hand_second_card);
+        let first_hand = CanonicalHand::Single(split_card) + first_hand_second_card;
+
+        let first_hand_ev = if can_act_after {
+            ev(actions_allowed_after, first_hand, splits_allowed_after, upcard, deck_after_first_card.clone()).ev
+        } else {
+            ev_of(&distribution_stand(first_hand, upcard, deck_after_first_card.clone()))
+        };
+        let first_hand_leaves = descendant_hand_leaves(
+            split_card, first_hand_second_card, splits_allowed, can_act_after, upcard, deck_after_first_card,
+        );
+
+        let second_hand_ev: f64 = first_hand_leaves.iter()
+            .map(|(p, leaf_deck)| p * ev_split_descendant(split_card, splits_allowed, upcard, leaf_deck))
+            .sum();
+
+        cumul_ev += p_next_card_is[first_hand_second_card] * (first_hand_ev + second_hand_ev);
+    }
+
+    cumul_ev + p_dealer_natural_after_draw(upcard, &deck)
+}
+
+/// The payoff distribution of a single hand formed by pairing `split_card` with a freshly drawn
+/// second card, exactly as happens immediately after a split. Distributional analogue of
+/// [`ev_split_descendant`].
+fn distribution_split_descendant(split_card: Rank, splits_allowed: u32, upcard: Rank, deck: &Deck) -> PayoffDistribution {
+    let can_act_after = RULES.hit_split_aces || split_card != A;
+    let mut actions_allowed_after = EnumMap::default();
+    actions_allowed_after[Action::Stand] = true;
+    actions_allowed_after[Action::Hit] = can_act_after;
+
+    let p_next_card_is = p_next_card_is_each(deck, true, true);
+    let mut distribution = PayoffDistribution::new();
     for new_second_card in RANKS {
         if p_next_card_is[new_second_card] <= 0f64 {
             continue;
@@ -181,22 +563,212 @@ fn ev_split(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck:
         let splits_allowed_after = if actions_allowed_after[Action::Split] { splits_allowed - 1 } else { 0 };
 
         let deck_after_this_card = deck.removed(new_second_card);
-        if can_act_after {
-            let ev_with = ev(
-                actions_allowed_after,
-                CanonicalHand::Single(split_card) + new_second_card,
-                splits_allowed_after,
-                upcard,
-                deck_after_this_card
-            ).ev;
-            cumul_ev += ev_with * p_next_card_is[new_second_card];
+        let hand = CanonicalHand::Single(split_card) + new_second_card;
+        let hand_distribution = if can_act_after {
+            ev(actions_allowed_after, hand, splits_allowed_after, upcard, deck_after_this_card).distribution
         } else {
-            let ev_standing = ev_stand(CanonicalHand::Single(split_card) + new_second_card, upcard, deck_after_this_card);
-            cumul_ev += ev_standing * p_next_card_is[new_second_card];
+            distribution_stand(hand, upcard, deck_after_this_card)
+        };
+        for (payoff, &p) in &hand_distribution {
+            add_into(&mut distribution, payoff.0, p * p_next_card_is[new_second_card]);
         }
     }
 
-    cumul_ev * 2f64
+    distribution
+}
+
+/// Distributional analogue of [`ev_split_approx`]: the two resulting hands' distributions are
+/// identical (both drawn against the same deck), so the combined wager's distribution is their
+/// self-convolution.
+fn distribution_split_approx(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck: Deck) -> PayoffDistribution {
+    assert!(splits_allowed > 0);
+
+    let split_card = match player_hand {
+        CanonicalHand::Pair(r) => r,
+        _ => panic!("Tried to split a non-paired hand!")
+    };
+
+    let hand_distribution = distribution_split_descendant(split_card, splits_allowed, upcard, &deck);
+    let combined = convolve(&hand_distribution, &hand_distribution);
+    apply_dealer_natural_correction(&combined, p_dealer_natural_after_draw(upcard, &deck))
+}
+
+/// Distributional analogue of [`ev_split_exact`]: convolve the first hand's own distribution with
+/// the second hand's distribution as played out over every deck the first hand could have left
+/// behind (see [`descendant_hand_leaves`]), instead of assuming both hands draw from
+/// identically-shaped decks.
+fn distribution_split_exact(player_hand: CanonicalHand, splits_allowed: u32, upcard: Rank, deck: Deck) -> PayoffDistribution {
+    assert!(splits_allowed > 0);
+
+    let split_card = match player_hand {
+        CanonicalHand::Pair(r) => r,
+        _ => panic!("Tried to split a non-paired hand!")
+    };
+
+    let can_act_after = RULES.hit_split_aces || split_card != A;
+    let mut actions_allowed_after = EnumMap::default();
+    actions_allowed_after[Action::Stand] = true;
+    actions_allowed_after[Action::Hit] = can_act_after;
+
+    let p_next_card_is = p_next_card_is_each(&deck, true, true);
+    let mut distribution = PayoffDistribution::new();
+    for first_hand_second_card in RANKS {
+        if p_next_card_is[first_hand_second_card] <= 0f64 {
+            continue;
+        }
+
+        actions_allowed_after[Action::Split] = splits_allowed > 1 && first_hand_second_card == split_card;
+        let splits_allowed_after = if actions_allowed_after[Action::Split] { splits_allowed - 1 } else { 0 };
+
+        let deck_after_first_card = deck.removed(first_hand_second_card);
+        let first_hand = CanonicalHand::Single(split_card) + first_hand_second_card;
+
+        let first_hand_distribution = if can_act_after {
+            ev(actions_allowed_after, first_hand, splits_allowed_after, upcard, deck_after_first_card.clone()).distribution
+        } else {
+            distribution_stand(first_hand, upcard, deck_after_first_card.clone())
+        };
+        let first_hand_leaves = descendant_hand_leaves(
+            split_card, first_hand_second_card, splits_allowed, can_act_after, upcard, deck_after_first_card,
+        );
+
+        let mut second_hand_distribution = PayoffDistribution::new();
+        for (p, leaf_deck) in &first_hand_leaves {
+            let leaf_distribution = distribution_split_descendant(split_card, splits_allowed, upcard, leaf_deck);
+            for (payoff, &lp) in &leaf_distribution {
+                add_into(&mut second_hand_distribution, payoff.0, lp * p);
+            }
+        }
+
+        let combined = convolve(&first_hand_distribution, &second_hand_distribution);
+        for (payoff, &p) in &combined {
+            add_into(&mut distribution, payoff.0, p * p_next_card_is[first_hand_second_card]);
+        }
+    }
+
+    apply_dealer_natural_correction(&distribution, p_dealer_natural_after_draw(upcard, &deck))
+}
+
+/// The leaf deck distribution (see [`deck_distribution_after`]) for one descendant hand of a
+/// split: `split_card` paired with `drawn_card`, played out from `deck_after_drawn_card`.
+fn descendant_hand_leaves(
+    split_card: Rank,
+    drawn_card: Rank,
+    splits_allowed: u32,
+    can_act_after: bool,
+    upcard: Rank,
+    deck_after_drawn_card: Deck,
+) -> Vec<(f64, Deck)> {
+    if !can_act_after {
+        return vec![(1f64, deck_after_drawn_card)];
+    }
+
+    let mut actions_allowed = EnumMap::default();
+    actions_allowed[Action::Stand] = true;
+    actions_allowed[Action::Hit] = true;
+    actions_allowed[Action::Split] = splits_allowed > 1 && drawn_card == split_card;
+    let splits_allowed_after = if actions_allowed[Action::Split] { splits_allowed - 1 } else { 0 };
+
+    let hand = CanonicalHand::Single(split_card) + drawn_card;
+    deck_distribution_after(actions_allowed, hand, splits_allowed_after, upcard, deck_after_drawn_card)
+}
+
+/// Every `Deck` that can remain once `hand` is played to completion under perfect strategy, each
+/// paired with its probability of occurring. Used by [`ev_split_exact`] to thread one split
+/// hand's card depletion into the other hand's evaluation instead of assuming they draw from
+/// identically-shaped decks. Recurses into nested splits, so this can blow up for deep
+/// `split_hands_limit`s - it is only ever reached when `exact_split_resolution` is enabled.
+fn deck_distribution_after(
+    allowed_actions: EnumMap<Action, bool>,
+    hand: CanonicalHand,
+    splits_allowed: u32,
+    upcard: Rank,
+    deck: Deck,
+) -> Vec<(f64, Deck)> {
+    if hand == Busted {
+        return vec![(1f64, deck)];
+    }
+
+    match ev(allowed_actions, hand, splits_allowed, upcard, deck.clone()).action {
+        Action::Stand | Action::Surrender => vec![(1f64, deck)],
+
+        Action::Double => {
+            let p_next_card_is = p_next_card_is_each(&deck, true, true);
+            RANKS.filter(|&next_card| p_next_card_is[next_card] > 0f64)
+                .map(|next_card| (p_next_card_is[next_card], deck.removed(next_card)))
+                .collect()
+        }
+
+        Action::Hit => {
+            let mut actions_after = EnumMap::default();
+            actions_after[Action::Stand] = true;
+            actions_after[Action::Hit] = true;
+
+            let p_next_card_is = p_next_card_is_each(&deck, true, true);
+            let mut leaves = vec![];
+            for next_card in RANKS {
+                if p_next_card_is[next_card] <= 0f64 {
+                    continue;
+                }
+
+                let deck_after_this_card = deck.removed(next_card);
+                let new_hand = hand + next_card;
+                if new_hand == Busted {
+                    leaves.push((p_next_card_is[next_card], deck_after_this_card));
+                } else {
+                    for (p, leaf_deck) in deck_distribution_after(actions_after, new_hand, 0, upcard, deck_after_this_card) {
+                        leaves.push((p_next_card_is[next_card] * p, leaf_deck));
+                    }
+                }
+            }
+            leaves
+        }
+
+        Action::Split => {
+            // A nested resplit: resolve the two new descendant hands sequentially (first hand's
+            // full play, then thread its resulting deck into the second hand's own draw and
+            // play), exactly like the top-level split in `ev_split_exact`.
+            let split_card = match hand {
+                CanonicalHand::Pair(r) => r,
+                _ => panic!("Tried to split a non-paired hand!")
+            };
+            let can_act_after = RULES.hit_split_aces || split_card != A;
+
+            let p_first_card_is = p_next_card_is_each(&deck, true, true);
+            let mut leaves = vec![];
+            for first_card in RANKS {
+                if p_first_card_is[first_card] <= 0f64 {
+                    continue;
+                }
+
+                let first_hand_leaves = descendant_hand_leaves(
+                    split_card, first_card, splits_allowed, can_act_after, upcard, deck.removed(first_card),
+                );
+
+                for (p1, deck_after_first_hand) in first_hand_leaves {
+                    let p_second_card_is = p_next_card_is_each(&deck_after_first_hand, true, true);
+                    for second_card in RANKS {
+                        if p_second_card_is[second_card] <= 0f64 {
+                            continue;
+                        }
+
+                        let second_hand_leaves = descendant_hand_leaves(
+                            split_card, second_card, splits_allowed, can_act_after, upcard,
+                            deck_after_first_hand.removed(second_card),
+                        );
+
+                        for (p2, leaf_deck) in second_hand_leaves {
+                            leaves.push((
+                                p_first_card_is[first_card] * p1 * p_second_card_is[second_card] * p2,
+                                leaf_deck,
+                            ));
+                        }
+                    }
+                }
+            }
+            leaves
+        }
+    }
 }
 
 /// Probabilities that the next card out of a deck is each rank.
@@ -233,7 +805,11 @@ fn p_next_card_is_each(deck: &Deck, can_be_ten: bool, can_be_ace: bool) -> RankA
 }
 
 /// Probability dealer beats this score / pushes with this score.
-/// Note: Assumes that the dealer already checked for Blackjack!
+/// Note: When [`BlackjackRules::dealer_peeks`] is set (American rules), assumes that the dealer
+/// already checked for Blackjack, so a dealer natural never appears in this recursion. Under ENHC
+/// (`dealer_peeks: false`), the dealer's second card is drawn the same as any other and can still
+/// complete a natural; see [`p_dealer_natural`] for the wager-size correction this requires in
+/// `distribution_double` and the split functions.
 #[memoize(Capacity: 10_000)]
 fn dealer_probabilities_beating(player_hand_to_beat: u32, dealer_hand: TotalHashedDealerHand, deck: Deck) -> (f64, f64) {
     // Base cases - the dealer is finished playing.
@@ -251,9 +827,10 @@ fn dealer_probabilities_beating(player_hand_to_beat: u32, dealer_hand: TotalHash
     }
 
     // Recursive cases - the dealer still has to pick one or more cards.
-    // Dealer already checked for Blackjack.
-    let next_can_be_ten = !(dealer_hand.is_one && dealer_hand.total == 11);
-    let next_can_be_ace = !(dealer_hand.is_one && dealer_hand.total == 10);
+    // When the dealer peeks, they've already checked for (and ruled out) Blackjack, so the
+    // blackjack-completing rank is excluded here. Under ENHC there's no peek to rule it out.
+    let next_can_be_ten = !(RULES.dealer_peeks && dealer_hand.is_one && dealer_hand.total == 11);
+    let next_can_be_ace = !(RULES.dealer_peeks && dealer_hand.is_one && dealer_hand.total == 10);
     let p_next_card_is = p_next_card_is_each(&deck, next_can_be_ten, next_can_be_ace);
     let mut cumul_prob_dealer_win = 0f64;
     let mut cumul_prob_push = 0f64;
@@ -310,6 +887,109 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_distribution_matches_ev_and_sums_to_one() {
+        // The payoff distribution for the chosen action is a regression check on `ev` itself:
+        // its probabilities must sum to 1, and its mean must exactly reproduce the scalar `ev`
+        // that was already being returned before distributions existed.
+        let deck: Deck = shoe!(DECKS);
+        let player = CanonicalHand::Hard2Card(16);
+        let allowed_actions = enum_map! {
+            Action::Stand => true,
+            Action::Hit => true,
+            Action::Double => false,
+            Action::Split => false,
+            Action::Surrender => false,
+        };
+
+        let evx = ev(allowed_actions, player, 0, 7, deck);
+
+        let total_p: f64 = evx.distribution.values().sum();
+        assert!((total_p - 1f64).abs() < 1e-9, "probabilities summed to {}", total_p);
+        assert!((ev_of(&evx.distribution) - evx.ev).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_split_distribution_matches_ev() {
+        // Both split resolution modes must keep their distribution's mean in sync with the
+        // scalar EV they've always returned, same regression check as above but through the
+        // convolution path.
+        let deck: Deck = shoe!(DECKS);
+        let approx = distribution_split_approx(CanonicalHand::Pair(8), 1, 6, deck.clone());
+        let exact = distribution_split_exact(CanonicalHand::Pair(8), 1, 6, deck.clone());
+
+        assert!((ev_of(&approx) - ev_split_approx(CanonicalHand::Pair(8), 1, 6, deck.clone())).abs() < 1e-9);
+        assert!((ev_of(&exact) - ev_split_exact(CanonicalHand::Pair(8), 1, 6, deck)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_strategy_chart() {
+        let deck: Deck = shoe!(DECKS);
+        let chart = strategy_chart(&deck);
+
+        // 33 distinct reachable 2-card hands (Hard 5-19, Soft 13-20, 10 Pairs) excluding
+        // Blackjack, times 10 dealer upcards.
+        assert_eq!(chart.entries.len(), 33 * 10);
+
+        // The chart should serialize to JSON, tagged with the rules it was computed under.
+        let json = chart.to_json().expect("Couldn't serialize strategy chart");
+        assert!(json.contains(&chart.rules));
+
+        // A pair of Aces vs. a dealer 6 should always be a Split.
+        let aces_vs_6 = chart.entries.iter()
+            .find(|e| e.hand == CanonicalHand::Pair(A) && e.dealer_up == 6)
+            .expect("Pair(A) vs 6 missing from chart");
+        assert_eq!(aces_vs_6.result.action, Action::Split);
+    }
+
+    #[test]
+    fn test_p_dealer_natural_peeking_ruleset() {
+        // The configured RULES has the dealer peek, so a dealer natural is ruled out before the
+        // player acts and never needs the ENHC wager-size correction.
+        let deck: Deck = shoe!(DECKS);
+        assert_eq!(p_dealer_natural(A, &deck), 0f64);
+        assert_eq!(p_dealer_natural(T, &deck), 0f64);
+        assert_eq!(p_dealer_natural(5, &deck), 0f64);
+    }
+
+    #[test]
+    fn test_deck_distribution_after_sums_to_one() {
+        // A plain stand-or-hit decision tree (no splitting) should still account for every card.
+        let deck: Deck = shoe!(DECKS);
+        let allowed_actions = enum_map! {
+            Action::Stand => true,
+            Action::Hit => true,
+            Action::Double => false,
+            Action::Split => false,
+            Action::Surrender => false,
+        };
+        let leaves = deck_distribution_after(allowed_actions, CanonicalHand::Hard2Card(16), 0, 7, deck);
+        let total_p: f64 = leaves.iter().map(|(p, _)| p).sum();
+        assert!((total_p - 1f64).abs() < 1e-9, "probabilities summed to {}", total_p);
+    }
+
+    #[test]
+    fn test_ev_split_exact_and_approx_both_finite() {
+        // With no resplits possible, exact and approximate split resolution should both produce
+        // a sane EV rather than diverging or blowing up.
+        let deck: Deck = shoe!(DECKS);
+        let approx = ev_split_approx(CanonicalHand::Pair(8), 1, 6, deck.clone());
+        let exact = ev_split_exact(CanonicalHand::Pair(8), 1, 6, deck);
+        assert!(approx.is_finite());
+        assert!(exact.is_finite());
+    }
+
+    #[test]
+    fn test_insurance_ev() {
+        // A deck with no Tens left should never be worth insuring.
+        let no_tens: Deck = deck![0, 4, 4, 4, 4, 4, 4, 4, 4, 4];
+        assert_eq!(insurance_ev(&no_tens), -1f64);
+
+        // A deck that's nothing but Tens is a guaranteed insurance win.
+        let all_tens: Deck = deck![16, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(insurance_ev(&all_tens), 1f64);
+    }
+
     #[test]
     fn test_simulate_hand() {
         // No double possible
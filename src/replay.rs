@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+use crate::hand::Hand;
+use crate::types::{Action, Rank};
+
+/// A single player decision point recorded during a replayed hand: which hand it was, what the
+/// player was allowed to do, what they chose, and (when the decision came from
+/// [`crate::simulation::PlayerDecisionMethod::PerfectStrategy`] or `BasicPerfectComparison`) the
+/// EV of each available action that justified the choice.
+#[derive(Serialize, Clone, Debug)]
+pub struct DecisionPoint {
+    /// The player's cards at the moment of this decision.
+    pub hand: Vec<Rank>,
+    pub allowed_actions: Vec<Action>,
+    pub chosen: Action,
+    /// `(action, ev)` pairs, present only when the decision method computed EVs.
+    pub evs: Option<Vec<(Action, f64)>>,
+}
+
+/// The final, settled state of one player hand (there may be more than one after splitting).
+#[derive(Serialize, Clone, Debug)]
+pub struct SettledHand {
+    pub final_cards: Vec<Rank>,
+    pub bet_units: f64,
+    pub roi: f64,
+}
+
+/// A structured record of everything that happened during one hand, suitable for dumping to disk
+/// as JSON and diffing against other replays or feeding to an external viewer.
+#[derive(Serialize, Clone, Debug)]
+pub struct HandReplay {
+    pub dealer_up: Rank,
+    pub dealer_down: Rank,
+    pub player_initial: Vec<Rank>,
+    pub decisions: Vec<DecisionPoint>,
+    pub dealer_final: Vec<Rank>,
+    pub settled_hands: Vec<SettledHand>,
+}
+
+impl HandReplay {
+    pub fn new(dealer_up: Rank, dealer_down: Rank, player_initial: &Hand) -> Self {
+        HandReplay {
+            dealer_up,
+            dealer_down,
+            player_initial: player_initial.cards.clone(),
+            decisions: vec![],
+            dealer_final: vec![],
+            settled_hands: vec![],
+        }
+    }
+
+    /// Serialize this replay to a JSON string.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
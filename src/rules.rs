@@ -1,7 +1,34 @@
 use std::fmt;
 use std::fmt::Formatter;
 
-#[derive(Debug, Clone, Copy)]
+/// Which player totals may be doubled down on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DoublePolicy {
+    /// Any two-card total, hard or soft, may be doubled.
+    AnyTwoCards,
+    /// Only hard 9, 10, or 11 may be doubled.
+    NineTenEleven,
+    /// Only hard 10 or 11 may be doubled.
+    TenEleven,
+    /// Doubling is not offered at all.
+    None,
+}
+
+impl DoublePolicy {
+    /// Whether a two-card hand totaling `total` may be doubled under this policy. Callers are
+    /// responsible for checking elsewhere (card count, `double_after_split`) anything this policy
+    /// alone doesn't cover.
+    pub fn allows(&self, total: u32) -> bool {
+        match self {
+            DoublePolicy::AnyTwoCards => true,
+            DoublePolicy::NineTenEleven => (9..=11).contains(&total),
+            DoublePolicy::TenEleven => (10..=11).contains(&total),
+            DoublePolicy::None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct BlackjackRules {
     pub decks: u32,
     pub shuffle_at_cards: u32,
@@ -9,11 +36,26 @@ pub struct BlackjackRules {
     pub hit_soft_17: bool,
     pub split_hands_limit: u32,
     pub split_aces_limit: u32,
-    pub double_any_hands: bool,
-    // 9 => 9-11; 10 => 10-11. Only considered when !DOUBLE_ANY_HANDS.
-    pub double_hard_hands_thru_11: u32,
+    /// Which two-card totals may be doubled down on.
+    pub double_policy: DoublePolicy,
     pub double_after_split: bool,
     pub hit_split_aces: bool,
+    /// Whether the player may surrender (forfeit the hand for half the bet back) on their initial
+    /// two-card hand once the dealer is known not to have Blackjack.
+    pub late_surrender: bool,
+    /// Whether the player may surrender before the dealer peeks for Blackjack, so surrender can
+    /// still apply even when the dealer turns up a natural.
+    pub early_surrender: bool,
+    /// Whether the dealer checks their hole card for Blackjack before the player acts (American
+    /// rules). When `false` (European No Hole Card / ENHC), the dealer draws their second card
+    /// only after the player finishes acting, so a dealer natural can still show up and reclaim
+    /// any doubled or split wagers down to the player's original bet.
+    pub dealer_peeks: bool,
+    /// Whether `perfect_strategy::ev` resolves split hands sequentially against a shrinking
+    /// shoe (exact, but blows up the state space) instead of evaluating both hands independently
+    /// against the same post-split deck and doubling (fast, but double-counts the cards one hand
+    /// would have removed from the other).
+    pub exact_split_resolution: bool,
 }
 
 pub const RULES_1D_H17_NDAS_D10: BlackjackRules = BlackjackRules {
@@ -23,10 +65,13 @@ pub const RULES_1D_H17_NDAS_D10: BlackjackRules = BlackjackRules {
     hit_soft_17: true,
     split_hands_limit: 4,
     split_aces_limit: 2,
-    double_any_hands: false,        // D10
-    double_hard_hands_thru_11: 10,  // D10
-    double_after_split: false,      // NDAS
+    double_policy: DoublePolicy::TenEleven,  // D10
+    double_after_split: false,               // NDAS
     hit_split_aces: false,
+    late_surrender: false,
+    early_surrender: false,
+    dealer_peeks: true,
+    exact_split_resolution: false,
 };
 
 pub const RULES_6D_H17_DAS_DANY: BlackjackRules = BlackjackRules {
@@ -36,25 +81,36 @@ pub const RULES_6D_H17_DAS_DANY: BlackjackRules = BlackjackRules {
     hit_soft_17: true,
     split_hands_limit: 4,
     split_aces_limit: 2,
-    double_any_hands: true,
-    double_hard_hands_thru_11: 10,
+    double_policy: DoublePolicy::AnyTwoCards,
     double_after_split: true,
     hit_split_aces: false,
+    late_surrender: false,
+    early_surrender: false,
+    dealer_peeks: true,
+    exact_split_resolution: false,
+};
+
+/// A 6-deck European No Hole Card shoe game, otherwise matching [`RULES_6D_H17_DAS_DANY`].
+pub const RULES_ENHC: BlackjackRules = BlackjackRules {
+    dealer_peeks: false,
+    ..RULES_6D_H17_DAS_DANY
 };
 
 impl fmt::Display for BlackjackRules {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let dbl_thru_11_str = self.double_hard_hands_thru_11.to_string();
-        write!(f, "{decks}D {hsvtn}17 {bjm}xBJ D{dbl} {das}DAS {hsa}{splits}S {asplits}SA {pen}pen",
+        write!(f, "{decks}D {hsvtn}17 {bjm}xBJ D{dbl} {das}DAS {hsa}{enhc}{splits}S {asplits}SA {pen}pen",
             decks=self.decks,
             hsvtn=if self.hit_soft_17 { "H" } else { "S" },
             bjm=self.blackjack_multiplier,
-            dbl=match (self.double_any_hands, self.double_hard_hands_thru_11) {
-                (true, _) => "any",
-                (false, _) => dbl_thru_11_str.as_str(),
+            dbl=match self.double_policy {
+                DoublePolicy::AnyTwoCards => "any",
+                DoublePolicy::NineTenEleven => "9",
+                DoublePolicy::TenEleven => "10",
+                DoublePolicy::None => "none",
             },
             das=if self.double_after_split { "" } else { "N" },
             hsa=if self.hit_split_aces { "HSA " } else { "" },
+            enhc=if self.dealer_peeks { "" } else { "ENHC " },
             splits=self.split_hands_limit,
             asplits=self.split_aces_limit,
             pen=self.shuffle_at_cards,
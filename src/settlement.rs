@@ -0,0 +1,156 @@
+//! Settlement of a completed round: turning finished player/dealer hands into a currency-unit
+//! payout. Everything here is a pure function of the final hands - how they were played (basic
+//! strategy, perfect strategy, card counting, ...) is irrelevant by this point.
+
+use std::cmp::Ordering;
+
+use crate::deck::Deck;
+use crate::hand::Hand;
+use crate::perfect_strategy;
+use crate::rules::BlackjackRules;
+use crate::types::T;
+
+/// The result of comparing a non-busted, non-blackjack player hand against the dealer's.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HandOutcome {
+    Win,
+    Push,
+    Loss,
+}
+
+/// Whether `hand` is a "blackjack": a two-card 21. A 21 reached via a hit or double does not
+/// count. A [Hand] carries no record of having been split, so a two-card 21 formed after a split
+/// does count here.
+pub(crate) fn is_blackjack(hand: &Hand) -> bool {
+    hand.cards.len() == 2 && hand.total() == 21
+}
+
+/// Settle one player hand against the dealer's final hand for `bet` units, under `rules`.
+///
+/// A two-card player blackjack pays `rules.blackjack_multiplier` and beats a dealer's multi-card
+/// 21, but pushes against a dealer blackjack. A player total over 21 is an immediate loss
+/// regardless of the dealer's hand, including a dealer bust. Otherwise the higher non-bust total
+/// wins and equal totals push.
+pub fn settle(player: &Hand, dealer: &Hand, bet: f64, rules: &BlackjackRules) -> f64 {
+    if player.total() > 21 {
+        return -bet;
+    }
+
+    let player_blackjack = is_blackjack(player);
+    let dealer_blackjack = is_blackjack(dealer);
+    if player_blackjack || dealer_blackjack {
+        return match (player_blackjack, dealer_blackjack) {
+            (true, true) => 0.0,
+            (true, false) => rules.blackjack_multiplier * bet,
+            (false, true) => -bet,
+            (false, false) => unreachable!(),
+        };
+    }
+
+    match outcome(player, dealer) {
+        HandOutcome::Win => bet,
+        HandOutcome::Push => 0.0,
+        HandOutcome::Loss => -bet,
+    }
+}
+
+/// The [HandOutcome] of `player` against `dealer` by total alone, ignoring blackjacks - use
+/// [settle] to fold blackjack payouts in and get a currency-unit result directly.
+pub fn outcome(player: &Hand, dealer: &Hand) -> HandOutcome {
+    let player_total = if player.total() > 21 { 0 } else { player.total() };
+    let dealer_total = if dealer.total() > 21 { 0 } else { dealer.total() };
+    match player_total.cmp(&dealer_total) {
+        Ordering::Greater => HandOutcome::Win,
+        Ordering::Equal => HandOutcome::Push,
+        Ordering::Less => HandOutcome::Loss,
+    }
+}
+
+/// Settle the insurance side bet, offered for `insurance_bet` units whenever the dealer shows an
+/// Ace. Pays 2:1 if the dealer's down card completes a Blackjack, otherwise the bet is lost.
+pub fn settle_insurance(dealer_down_card: crate::types::Rank, insurance_bet: f64) -> f64 {
+    if dealer_down_card == T {
+        2.0 * insurance_bet
+    } else {
+        -insurance_bet
+    }
+}
+
+/// Whether a player blackjack against a dealer Ace should take "even money" - a guaranteed 1:1
+/// payout taken immediately instead of risking a push against a dealer blackjack. This is
+/// mathematically identical to taking insurance on the blackjack, so it is favorable under
+/// exactly the same condition: [`perfect_strategy::insurance_ev`] positive, i.e. more than half of
+/// the unseen cards (including the dealer's hole card) are Tens.
+///
+/// # Arguments
+/// * `deck` - The remaining draw pile, INCLUDING the Dealer's unknown down card.
+pub fn even_money_favorable(deck: &Deck) -> bool {
+    perfect_strategy::insurance_ev(deck) > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deck::Deck;
+    use crate::hand;
+    use crate::rules::RULES_1D_H17_NDAS_D10;
+    use crate::types::{A, T};
+
+    use super::*;
+
+    #[test]
+    fn test_settle_player_bust_always_loses() {
+        let player = hand![T, 8, 6];
+        let dealer = hand![T, 8];
+        assert_eq!(settle(&player, &dealer, 1.0, &RULES_1D_H17_NDAS_D10), -1.0);
+    }
+
+    #[test]
+    fn test_settle_player_blackjack_pays_multiplier() {
+        let player = hand![A, T];
+        let dealer = hand![T, 8];
+        assert_eq!(settle(&player, &dealer, 2.0, &RULES_1D_H17_NDAS_D10), 3.0);
+    }
+
+    #[test]
+    fn test_settle_both_blackjack_pushes() {
+        let player = hand![A, T];
+        let dealer = hand![A, T];
+        assert_eq!(settle(&player, &dealer, 1.0, &RULES_1D_H17_NDAS_D10), 0.0);
+    }
+
+    #[test]
+    fn test_settle_dealer_blackjack_beats_non_blackjack_21() {
+        let player = hand![7, 7, 7];
+        let dealer = hand![A, T];
+        assert_eq!(settle(&player, &dealer, 1.0, &RULES_1D_H17_NDAS_D10), -1.0);
+    }
+
+    #[test]
+    fn test_settle_falls_back_to_outcome_when_neither_is_blackjack() {
+        let player = hand![T, 9];
+        let dealer = hand![T, 7];
+        assert_eq!(settle(&player, &dealer, 1.0, &RULES_1D_H17_NDAS_D10), 1.0);
+    }
+
+    #[test]
+    fn test_outcome_treats_dealer_bust_as_a_win() {
+        let player = hand![T, 9];
+        let dealer = hand![T, 6, 6];
+        assert_eq!(outcome(&player, &dealer), HandOutcome::Win);
+    }
+
+    #[test]
+    fn test_settle_insurance_pays_two_to_one_on_dealer_ten() {
+        assert_eq!(settle_insurance(T, 0.5), 1.0);
+        assert_eq!(settle_insurance(9, 0.5), -0.5);
+    }
+
+    #[test]
+    fn test_even_money_favorable_at_rich_and_poor_ten_counts() {
+        let ten_heavy = Deck::new([10, 1, 1, 1, 1, 1, 1, 1, 1, 1]);
+        assert!(even_money_favorable(&ten_heavy));
+
+        let ten_light = Deck::new([1, 1, 5, 5, 5, 5, 5, 5, 5, 5]);
+        assert!(!even_money_favorable(&ten_light));
+    }
+}
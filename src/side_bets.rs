@@ -0,0 +1,86 @@
+//! Suit-dependent side bets offered alongside the main game: Perfect Pairs (on the player's first
+//! two cards) and 21+3 (the player's first two cards plus the dealer's up card, scored as a
+//! 3-card poker hand). Both require knowing card suits, so they only apply when playing against a
+//! [crate::deck::Deck] built with [crate::deck::Deck::full_shoe_with_suits].
+
+use crate::types::Card;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SideBet {
+    PerfectPairs,
+    TwentyOnePlusThree,
+}
+
+/// Net payout (in side-bet units, e.g. `25.0` for a 25:1 win, `-1.0` for a loss) for each
+/// requested side bet.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct SideBetPayouts {
+    pub perfect_pairs: f64,
+    pub twenty_one_plus_three: f64,
+}
+
+impl SideBetPayouts {
+    pub fn total(&self) -> f64 {
+        self.perfect_pairs + self.twenty_one_plus_three
+    }
+}
+
+/// Settle every requested side bet for this hand, at 1 unit staked per bet.
+pub fn evaluate(bets: &[SideBet], player_card_1: Card, player_card_2: Card, dealer_up: Card) -> SideBetPayouts {
+    let mut payouts = SideBetPayouts::default();
+    for bet in bets {
+        match bet {
+            SideBet::PerfectPairs => payouts.perfect_pairs = perfect_pairs_payout(player_card_1, player_card_2),
+            SideBet::TwentyOnePlusThree => payouts.twenty_one_plus_three = twenty_one_plus_three_payout(player_card_1, player_card_2, dealer_up),
+        }
+    }
+    payouts
+}
+
+/// Perfect Pairs pays on the player's first two cards alone: 25:1 for an exact suited pair, 12:1
+/// for a pair of the same color, 6:1 for a mixed-color pair, and a loss otherwise.
+fn perfect_pairs_payout(a: Card, b: Card) -> f64 {
+    if a.rank != b.rank {
+        return -1.0;
+    }
+    if a.suit == b.suit {
+        25.0
+    } else if a.suit.same_color_as(&b.suit) {
+        12.0
+    } else {
+        6.0
+    }
+}
+
+/// 21+3 scores the player's two cards plus the dealer's up card as a 3-card poker hand: suited
+/// trips pay 100:1, a straight flush 40:1, three of a kind 30:1, a straight 10:1, and a flush
+/// 5:1.
+fn twenty_one_plus_three_payout(a: Card, b: Card, c: Card) -> f64 {
+    let mut ranks = [bj_rank_to_poker_value(a.rank), bj_rank_to_poker_value(b.rank), bj_rank_to_poker_value(c.rank)];
+    ranks.sort_unstable();
+
+    let is_flush = a.suit == b.suit && b.suit == c.suit;
+    let is_trips = ranks[0] == ranks[1] && ranks[1] == ranks[2];
+    let is_straight = !is_trips && (
+        (ranks[0] + 1 == ranks[1] && ranks[1] + 1 == ranks[2])
+            || ranks == [2, 3, 14] // Ace-low straight (A-2-3)
+    );
+
+    match (is_trips, is_straight, is_flush) {
+        (true, _, true) => 100.0,
+        (false, true, true) => 40.0,
+        (true, _, false) => 30.0,
+        (false, true, false) => 10.0,
+        (false, false, true) => 5.0,
+        (false, false, false) => -1.0,
+    }
+}
+
+/// Poker hand rank value (2-14, Ace high) for a blackjack [Rank], so straights can be detected.
+fn bj_rank_to_poker_value(rank: crate::types::Rank) -> u32 {
+    match rank {
+        crate::types::T => 10,
+        crate::types::A => 14,
+        n => n,
+    }
+}
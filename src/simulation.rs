@@ -2,12 +2,17 @@ use std::cmp::Ordering;
 
 use derive_more::{Add, AddAssign};
 use enum_map::enum_map;
+use rand::Rng;
 
-use crate::{composition_strategy, hand, perfect_strategy, RULES, strategy_comparison};
+use crate::{composition_strategy, hand, perfect_strategy, RULES, settlement, strategy_comparison};
 use crate::basic_strategy::BasicStrategyChart;
+use crate::counting::{self, CountBucketStats, CountingPlay};
 use crate::deck::Deck;
 use crate::hand::*;
 use crate::hand::canonical_hand::CanonicalHand;
+use crate::replay::{DecisionPoint, HandReplay, SettledHand};
+use crate::side_bets::SideBet;
+use crate::statistics::RoiAccumulator;
 use crate::strategy_comparison::BasicPerfectComparison;
 use crate::types::*;
 
@@ -23,6 +28,17 @@ pub struct SimulationResult {
     pub insurances_won: u64,
     /// Return on Investment
     pub roi: f64,
+    /// Online mean/variance of ROI per hand, for a confidence interval on the house edge.
+    pub roi_stats: RoiAccumulator,
+
+    /// Units staked on side bets (Perfect Pairs, 21+3), one unit per bet per hand.
+    pub side_bet_units_placed: f64,
+    /// Net return from side bets, separate from the main game's `roi`.
+    pub side_bet_roi: f64,
+
+    /// Units wagered and ROI broken down by true-count bucket, only populated when playing under
+    /// [PlayerDecisionMethod::CountingStrategy].
+    pub count_stats: CountBucketStats,
 }
 
 pub enum PlayerDecisionMethod<'a> {
@@ -30,6 +46,9 @@ pub enum PlayerDecisionMethod<'a> {
     CompositionStrategy,
     PerfectStrategy,
     BasicPerfectComparison(&'a BasicStrategyChart),
+    /// Play basic strategy with count-driven index-play deviations and bet sizing. See
+    /// [CountingPlay].
+    CountingStrategy(&'a CountingPlay<'a>),
 }
 
 /// Play out one complete hand with the given starting deck.
@@ -38,21 +57,153 @@ pub enum PlayerDecisionMethod<'a> {
 /// # Arguments
 /// * `deck` - State of the deck before the hand started. The deck will be mutated as some cards are
 ///            played during the hand.
+/// * `rng` - Source of randomness for every card dealt during the hand. Pass a seeded RNG (e.g.
+///           `StdRng::seed_from_u64`) to make the hand replayable from that seed.
 /// * `player_decision` - Function that will be called upon whenever there is a player decision to
 ///                       make.
 pub fn play_hand(
     deck: &mut Deck,
+    rng: &mut impl Rng,
     player_decision_method: PlayerDecisionMethod,
 ) -> (SimulationResult, BasicPerfectComparison) {
-    let mut dealer_hand = hand![deck.draw(), deck.draw()];
-    let mut player_hands: Vec<Hand> = vec![hand![deck.draw(), deck.draw()]];
-    let mut bet_units: Vec<f64> = vec![1.0];
+    let (result, comparison, _replay) = play_hand_impl(deck, rng, player_decision_method, false, &[]);
+    (result, comparison)
+}
+
+/// Identical to [play_hand], but also returns a [HandReplay] describing every decision point in
+/// the hand. Recording a replay re-derives EVs for each decision, so it costs noticeably more
+/// than [play_hand] and should only be used when a hand is actually worth inspecting.
+pub fn play_hand_recorded(
+    deck: &mut Deck,
+    rng: &mut impl Rng,
+    player_decision_method: PlayerDecisionMethod,
+) -> (SimulationResult, BasicPerfectComparison, HandReplay) {
+    let (result, comparison, replay) = play_hand_impl(deck, rng, player_decision_method, true, &[]);
+    (result, comparison, replay.expect("replay requested but not produced"))
+}
+
+/// Identical to [play_hand], but also settles the given suit-dependent `side_bets` against the
+/// player's first two cards and the dealer's up card, contributing their payouts into
+/// [SimulationResult::side_bet_roi]. `deck` must have been built with
+/// [crate::deck::Deck::full_shoe_with_suits].
+pub fn play_hand_with_side_bets(
+    deck: &mut Deck,
+    rng: &mut impl Rng,
+    player_decision_method: PlayerDecisionMethod,
+    side_bets: &[SideBet],
+) -> (SimulationResult, BasicPerfectComparison) {
+    let (result, comparison, _replay) = play_hand_impl(deck, rng, player_decision_method, false, side_bets);
+    (result, comparison)
+}
+
+fn play_hand_impl(
+    deck: &mut Deck,
+    rng: &mut impl Rng,
+    player_decision_method: PlayerDecisionMethod,
+    record_replay: bool,
+    side_bets: &[SideBet],
+) -> (SimulationResult, BasicPerfectComparison, Option<HandReplay>) {
+    // The true count is derived from the deck's composition before any cards for this hand are
+    // dealt, so it drives both this hand's bet size and its strategy deviations.
+    let true_count = match &player_decision_method {
+        PlayerDecisionMethod::CountingStrategy(cp) => Some(counting::true_count(deck, cp.num_decks, cp.system)),
+        _ => None,
+    };
+
+    let (dealer_c1, dealer_c2, player_c1, player_c2, side_bet_payouts) = if side_bets.is_empty() {
+        (deck.draw(rng), deck.draw(rng), deck.draw(rng), deck.draw(rng), None)
+    } else {
+        let dealer_up = deck.draw_suited(rng);
+        let dealer_down = deck.draw_suited(rng);
+        let player_1 = deck.draw_suited(rng);
+        let player_2 = deck.draw_suited(rng);
+        let payouts = crate::side_bets::evaluate(side_bets, player_1, player_2, dealer_up);
+        (dealer_up.rank, dealer_down.rank, player_1.rank, player_2.rank, Some(payouts))
+    };
+
+    let mut dealer_hand = hand![dealer_c1, dealer_c2];
+    let mut player_hands: Vec<Hand> = vec![hand![player_c1, player_c2]];
+    let initial_bet_units = match &player_decision_method {
+        PlayerDecisionMethod::CountingStrategy(cp) => cp.bet_ramp.bet_units(true_count.unwrap()),
+        _ => 1.0,
+    };
+    let mut bet_units: Vec<f64> = vec![initial_bet_units];
+    // Tracks the part of each hand's wager a late dealer natural can claim: the first hand's
+    // original ante, untouched by doubling, and nothing for hands created by splitting - under
+    // ENHC (`!RULES.dealer_peeks`) a dealer natural revealed only after the player has acted
+    // reclaims just the original bet, not any extra doubled or split on top of it (mirrors
+    // `apply_dealer_natural_correction` in perfect_strategy.rs's EV model for the same situation).
+    let mut original_bet_units: Vec<f64> = vec![initial_bet_units];
 
     let mut result = SimulationResult::default();
+    if let Some(payouts) = side_bet_payouts {
+        result.side_bet_units_placed += side_bets.len() as f64;
+        result.side_bet_roi += payouts.total();
+    }
+
+    let mut replay = if record_replay {
+        Some(HandReplay::new(dealer_hand[0], dealer_hand[1], &player_hands[0]))
+    } else {
+        None
+    };
+
     result.hands_started += 1;
 
     let mut comparison = BasicPerfectComparison::default();
 
+    // Early surrender (if enabled) is decided before the dealer peeks for Blackjack, so unlike
+    // late surrender it can still apply even when the dealer turns up a natural. Only offered
+    // when the dealer's upcard makes a natural possible at all.
+    if RULES.early_surrender && matches!(dealer_hand[0], A | T) {
+        let canonical_hand = CanonicalHand::from_cards(&player_hands[0]);
+        if perfect_strategy::can_surrender(&canonical_hand, 1) {
+            let deck_plus_down_card = deck.added(dealer_hand[1]);
+            let take_early_surrender = match player_decision_method {
+                PlayerDecisionMethod::PerfectStrategy | PlayerDecisionMethod::BasicPerfectComparison(_) => {
+                    let mut splits_allowed = 0;
+                    let allowed_if_continuing = enum_map! {
+                        Action::Stand => true,
+                        Action::Hit => true,
+                        Action::Double => RULES.double_policy.allows(player_hands[0].total()),
+                        Action::Split => match player_hands[0].is_pair() {
+                            Some(A) => { splits_allowed = RULES.split_aces_limit - 1; splits_allowed > 0 },
+                            Some(_) => { splits_allowed = RULES.split_hands_limit - 1; splits_allowed > 0 },
+                            None => false,
+                        },
+                        Action::Surrender => false,
+                    };
+                    let continue_ev = perfect_strategy::perfect_play(
+                        allowed_if_continuing, &canonical_hand, splits_allowed, dealer_hand[0], &deck_plus_down_card,
+                    ).ev;
+                    // `continue_ev` comes from the standard recursion, which (under a ruleset where
+                    // the dealer peeks) is itself conditioned on the dealer NOT holding a natural -
+                    // the peek would already have settled the hand otherwise. Early surrender is
+                    // decided before that peek, so it must weigh the risk of a dealer natural back
+                    // in (a guaranteed -1) before comparing against surrendering for -0.5.
+                    let p_dealer_natural = perfect_strategy::p_dealer_natural_unconditioned(
+                        dealer_hand[0], &deck_plus_down_card,
+                    );
+                    let risked_continue_ev = if RULES.dealer_peeks {
+                        p_dealer_natural * -1.0 + (1.0 - p_dealer_natural) * continue_ev
+                    } else {
+                        continue_ev
+                    };
+                    perfect_strategy::ev_surrender() > risked_continue_ev
+                },
+                _ => false,
+            };
+            if take_early_surrender {
+                let hand_roi = -0.5 * bet_units[0];
+                result.roi += hand_roi;
+                result.bet_units_placed += bet_units[0];
+                result.roi_stats.observe(result.roi);
+                observe_counting(&mut result, true_count, &bet_units);
+                settle_replay(&mut replay, &dealer_hand, &player_hands, &bet_units, &[hand_roi]);
+                return (result, comparison, replay);
+            }
+        }
+    }
+
     let take_insurance = if dealer_hand[0] == A {
         let deck_plus_down_card = deck.added(dealer_hand[1]);
         result.insurances_offered += 1;
@@ -68,6 +219,7 @@ pub fn play_hand(
                 }
                 choice
             },
+            PlayerDecisionMethod::CountingStrategy(cp) => cp.deviations.take_insurance(true_count.unwrap()),
             _ => false
         }
     } else { false };
@@ -77,30 +229,26 @@ pub fn play_hand(
         result.insurances_taken += 1;
         if dealer_hand[1] == T {
             result.insurances_won += 1;
-            result.roi += 1.0;
-        } else {
-            result.roi += -0.5;
         }
+        result.roi += settlement::settle_insurance(dealer_hand[1], 0.5);
     }
 
-    // Check for dealt Blackjacks (early return if so)
-    match (dealer_hand.total(), &player_hands[0].total()) {
-        (21, 21) => {
-            result.roi += 0f64;
-            result.bet_units_placed += 1.0;
-            return (result, comparison);
-        },
-        (21, _) => {
-            result.roi += -1f64;
-            result.bet_units_placed += 1.0;
-            return (result, comparison);
-        },
-        (_, 21) => {
-            result.roi += RULES.blackjack_multiplier;
-            result.bet_units_placed += 1.0;
-            return (result, comparison);
-        },
-        (_, _) => (),
+    // Check for dealt Blackjacks (early return if so). Both hands still have exactly their
+    // original two cards at this point, so a 21 here is always a natural and `settle` will price
+    // it (and any push against a dealer natural) correctly. Under ENHC (`!RULES.dealer_peeks`) the
+    // dealer doesn't check its hole card for a natural until after the player has finished acting,
+    // so a dealer natural alone must not short-circuit play here - only the player's own natural
+    // does (settling it immediately is still correct either way, since `settle` consults the
+    // dealer's actual hand and so still prices a push against a dealer natural correctly).
+    let dealer_natural = RULES.dealer_peeks && dealer_hand.total() == 21;
+    if dealer_natural || player_hands[0].total() == 21 {
+        let hand_roi = settlement::settle(&player_hands[0], &dealer_hand, bet_units[0], &RULES);
+        result.roi += hand_roi;
+        result.bet_units_placed += bet_units[0];
+        result.roi_stats.observe(result.roi);
+        observe_counting(&mut result, true_count, &bet_units);
+        settle_replay(&mut replay, &dealer_hand, &player_hands, &bet_units, &[hand_roi]);
+        return (result, comparison, replay);
     }
 
     // Player action
@@ -112,6 +260,7 @@ pub fn play_hand(
             let current_hand = &player_hands[hand_idx];
             let dealer_up = dealer_hand[0];
             let num_hands = player_hands.len() as u32;
+            let canonical_current_hand = CanonicalHand::from_cards(current_hand);
             // Special case: The player does not know the current dealer down card. For purposes of
             // strategy calculation, we need to act as though that card is still in the deck.
             let deck_plus_down_card  = deck.added(dealer_hand[1]);
@@ -122,13 +271,13 @@ pub fn play_hand(
                 Action::Hit => true,
                 Action::Double => current_hand.cards.len() == 2
                     && (RULES.double_after_split || num_hands == 1)
-                    && (RULES.double_any_hands ||
-                        (current_hand.total() >= RULES.double_hard_hands_thru_11 && current_hand.total() <= 11)),
+                    && RULES.double_policy.allows(current_hand.total()),
                 Action::Split => match current_hand.is_pair() {
                     Some(A) => { splits_allowed = RULES.split_aces_limit - num_hands; splits_allowed > 0 },
                     Some(_) => { splits_allowed = RULES.split_hands_limit - num_hands; splits_allowed > 0 },
                     None => false,
-                }
+                },
+                Action::Surrender => RULES.late_surrender && perfect_strategy::can_surrender(&canonical_current_hand, num_hands),
             };
 
             let decision = match player_decision_method {
@@ -139,7 +288,7 @@ pub fn play_hand(
                     composition_strategy::hand_composition_play(current_hand, num_hands, dealer_up, RULES.decks)
                 },
                 PlayerDecisionMethod::PerfectStrategy => {
-                    perfect_strategy::perfect_play(allowed_actions, &CanonicalHand::from_cards(current_hand), splits_allowed, dealer_up, &deck_plus_down_card).action
+                    perfect_strategy::perfect_play(allowed_actions, &canonical_current_hand, splits_allowed, dealer_up, &deck_plus_down_card).action
                 },
                 PlayerDecisionMethod::BasicPerfectComparison(basic_chart) => {
                     let (action, comp) = strategy_comparison::decide(
@@ -148,33 +297,69 @@ pub fn play_hand(
                     comparison += comp;
                     action
                 },
+                PlayerDecisionMethod::CountingStrategy(cp) => {
+                    cp.deviations.decide(cp.chart, current_hand, dealer_up, num_hands, true_count.unwrap())
+                },
             };
 
             result.decisions_made += 1;
 
+            if decision == Action::Surrender {
+                let hand_roi = -0.5 * bet_units[hand_idx];
+                result.roi += hand_roi;
+                result.bet_units_placed += bet_units[hand_idx];
+                result.roi_stats.observe(result.roi);
+                observe_counting(&mut result, true_count, &bet_units);
+                settle_replay(&mut replay, &dealer_hand, &player_hands, &bet_units, &[hand_roi]);
+                return (result, comparison, replay);
+            }
+
+            if let Some(replay) = &mut replay {
+                let evs = match player_decision_method {
+                    PlayerDecisionMethod::PerfectStrategy | PlayerDecisionMethod::BasicPerfectComparison(_) => {
+                        let calc = perfect_strategy::perfect_play(
+                            allowed_actions, &canonical_current_hand, splits_allowed, dealer_up, &deck_plus_down_card,
+                        );
+                        Some(calc.choices.iter()
+                            .filter(|(_, &ev)| ev != f64::NEG_INFINITY)
+                            .map(|(action, &ev)| (action, ev))
+                            .collect())
+                    },
+                    _ => None,
+                };
+                replay.decisions.push(DecisionPoint {
+                    hand: current_hand.cards.clone(),
+                    allowed_actions: allowed_actions.iter().filter(|(_, &allowed)| allowed).map(|(a, _)| a).collect(),
+                    chosen: decision,
+                    evs,
+                });
+            }
+
             match decision {
                 Action::Stand => { can_act_again_this_hand = false; }
-                Action::Hit => { player_hands[hand_idx] += deck.draw(); }
+                Action::Hit => { player_hands[hand_idx] += deck.draw(rng); }
                 Action::Double => {
                     bet_units[hand_idx] *= 2.0;
-                    player_hands[hand_idx] += deck.draw();
+                    player_hands[hand_idx] += deck.draw(rng);
                     can_act_again_this_hand = false;
                 }
                 Action::Split => {
                     // Create new hand at the end of the current list
                     let split_rank = player_hands[hand_idx][1];
-                    player_hands.push(hand![split_rank, deck.draw()]);
+                    player_hands.push(hand![split_rank, deck.draw(rng)]);
                     bet_units.push(bet_units[hand_idx]);
+                    original_bet_units.push(0.0);
 
                     // Draw and replace the second card in this current hand
-                    player_hands[hand_idx].cards[1] = deck.draw();
+                    player_hands[hand_idx].cards[1] = deck.draw(rng);
 
                     if !RULES.hit_split_aces && split_rank == A {
                         assert_eq!(RULES.split_aces_limit, 2, "TODO: Can't support resplit aces.");
-                        player_hands[hand_idx + 1].cards[1] = deck.draw();
+                        player_hands[hand_idx + 1].cards[1] = deck.draw(rng);
                         can_act_again_at_all = false;
                     }
                 }
+                Action::Surrender => unreachable!("surrender is handled by an early return above"),
             }
 
             if player_hands[hand_idx].total() > 21 {
@@ -198,7 +383,7 @@ pub fn play_hand(
                     break;
                 }
             }
-            dealer_hand += deck.draw();
+            dealer_hand += deck.draw(rng);
         }
     }
     let dealer_score = match dealer_hand.total() {
@@ -206,19 +391,55 @@ pub fn play_hand(
         t => t,
     };
 
-    // Sum up winnings
+    // Sum up winnings. A dealer natural can only be unseen this late under ENHC (with
+    // `RULES.dealer_peeks` the dealer peeks and settles it immediately above); in that case it
+    // beats every remaining hand, including a multi-card 21, but only reclaims the player's
+    // original ante, not any amount added by doubling or splitting.
+    let dealer_natural = !RULES.dealer_peeks && settlement::is_blackjack(&dealer_hand);
+    let mut hand_rois = vec![0f64; player_hands.len()];
     for (hand_idx, hand) in player_hands.iter().enumerate() {
         result.bet_units_placed += bet_units[hand_idx];
         let hand_score = match hand.total() {
             t if t > 21 => 0,
             t => t,
         };
-        match hand_score.cmp(&dealer_score) {
-            Ordering::Greater => { result.roi += bet_units[hand_idx]; }
-            Ordering::Equal => { /* Push */ }
-            Ordering::Less => { result.roi -= bet_units[hand_idx]; }
-        }
+        hand_rois[hand_idx] = match hand_score.cmp(&dealer_score) {
+            _ if dealer_natural => -original_bet_units[hand_idx],
+            Ordering::Greater => bet_units[hand_idx],
+            Ordering::Equal => 0f64,
+            Ordering::Less => -bet_units[hand_idx],
+        };
+        result.roi += hand_rois[hand_idx];
     }
 
-    (result, comparison)
+    result.roi_stats.observe(result.roi);
+    observe_counting(&mut result, true_count, &bet_units);
+    settle_replay(&mut replay, &dealer_hand, &player_hands, &bet_units, &hand_rois);
+    (result, comparison, replay)
+}
+
+/// Record this hand's total units wagered and ROI into `result.count_stats`, bucketed by
+/// `true_count`. No-op when the hand wasn't played under [PlayerDecisionMethod::CountingStrategy].
+fn observe_counting(result: &mut SimulationResult, true_count: Option<f64>, bet_units: &[f64]) {
+    if let Some(tc) = true_count {
+        result.count_stats.observe(tc, bet_units.iter().sum(), result.roi);
+    }
+}
+
+/// Fill in the final dealer hand and each player hand's settlement into `replay`, if recording.
+fn settle_replay(
+    replay: &mut Option<HandReplay>,
+    dealer_hand: &Hand,
+    player_hands: &[Hand],
+    bet_units: &[f64],
+    hand_rois: &[f64],
+) {
+    let Some(replay) = replay else { return };
+
+    replay.dealer_final = dealer_hand.cards.clone();
+    replay.settled_hands = player_hands.iter().enumerate().map(|(hand_idx, hand)| SettledHand {
+        final_cards: hand.cards.clone(),
+        bet_units: bet_units[hand_idx],
+        roi: hand_rois[hand_idx],
+    }).collect();
 }
@@ -1,10 +1,133 @@
-use derive_more::{Add, AddAssign};
-
-#[derive(Default, Add, AddAssign)]
-pub struct SimulationStatistics {
-    pub shoes_played: u64,
-    pub hands_played: u64,
-    pub decisions_made: u64,
-    /// Return on Investment in betting units
-    pub roi: f64,
+use std::ops::AddAssign;
+
+/// Online mean/variance accumulator for per-hand ROI, computed with Welford's algorithm so it can
+/// be updated one hand at a time without storing the full sample history.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct RoiAccumulator {
+    n: u64,
+    mean: f64,
+    /// Sum of squared differences from the running mean.
+    m2: f64,
+}
+
+impl RoiAccumulator {
+    /// Fold a single hand's ROI into the accumulator.
+    pub fn observe(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample variance of per-hand ROI.
+    pub fn variance(&self) -> f64 {
+        if self.n < 2 {
+            return f64::NAN;
+        }
+        self.m2 / (self.n - 1) as f64
+    }
+
+    /// Standard error of the mean.
+    pub fn standard_error(&self) -> f64 {
+        (self.variance() / self.n as f64).sqrt()
+    }
+
+    /// 95% confidence interval half-width (`1.96 * SE`) around the mean.
+    pub fn ci_95_half_width(&self) -> f64 {
+        1.96 * self.standard_error()
+    }
+
+    /// 95% confidence interval around the mean, as `(low, high)`.
+    pub fn ci_95(&self) -> (f64, f64) {
+        let hw = self.ci_95_half_width();
+        (self.mean - hw, self.mean + hw)
+    }
+}
+
+impl AddAssign for RoiAccumulator {
+    /// Merge another thread's accumulator into this one using the parallel form of Welford's
+    /// algorithm, rather than summing fields independently (which would not produce a correct
+    /// combined variance).
+    fn add_assign(&mut self, rhs: Self) {
+        if rhs.n == 0 {
+            return;
+        }
+        if self.n == 0 {
+            *self = rhs;
+            return;
+        }
+
+        let n = self.n + rhs.n;
+        let delta = rhs.mean - self.mean;
+        let mean = (self.n as f64 * self.mean + rhs.n as f64 * rhs.mean) / n as f64;
+        let m2 = self.m2 + rhs.m2 + delta * delta * self.n as f64 * rhs.n as f64 / n as f64;
+
+        self.n = n;
+        self.mean = mean;
+        self.m2 = m2;
+    }
+}
+
+impl std::ops::Add for RoiAccumulator {
+    type Output = Self;
+
+    /// Same merge as [AddAssign], by value.
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_naive_variance() {
+        let samples = [1.0, -1.0, 0.0, 1.5, -0.5, 1.0, 1.0, -1.0, 0.0, 2.0];
+
+        let mut acc = RoiAccumulator::default();
+        for &x in &samples {
+            acc.observe(x);
+        }
+
+        let naive_mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let naive_var = samples.iter().map(|x| (x - naive_mean).powi(2)).sum::<f64>()
+            / (samples.len() - 1) as f64;
+
+        assert!((acc.mean() - naive_mean).abs() < 1e-9);
+        assert!((acc.variance() - naive_var).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge_matches_single_pass() {
+        let samples = [1.0, -1.0, 0.0, 1.5, -0.5, 1.0, 1.0, -1.0, 0.0, 2.0];
+
+        let mut whole = RoiAccumulator::default();
+        for &x in &samples {
+            whole.observe(x);
+        }
+
+        let mut a = RoiAccumulator::default();
+        for &x in &samples[..4] {
+            a.observe(x);
+        }
+        let mut b = RoiAccumulator::default();
+        for &x in &samples[4..] {
+            b.observe(x);
+        }
+        a += b;
+
+        assert_eq!(a.n(), whole.n());
+        assert!((a.mean() - whole.mean()).abs() < 1e-9);
+        assert!((a.variance() - whole.variance()).abs() < 1e-9);
+    }
 }
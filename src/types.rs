@@ -32,12 +32,13 @@ impl<T> RankArray<T> where for <'a> T: Sum<&'a T> {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, enum_map::Enum)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, enum_map::Enum, serde::Serialize, serde::Deserialize)]
 pub enum Action {
     Stand,
     Hit,
     Double,
     Split,
+    Surrender,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
@@ -46,3 +47,35 @@ pub enum HandType {
     Soft,
     Pair,
 }
+
+/// The suit of a single physical card. Only needed for suit-dependent side bets (Perfect Pairs,
+/// 21+3) - the main game and strategy calculations only ever care about [Rank].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+pub const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+impl Suit {
+    /// Whether two suits share the same color (both red or both black), which several side bets
+    /// pay out on.
+    pub fn same_color_as(&self, other: &Suit) -> bool {
+        self.is_red() == other.is_red()
+    }
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Diamonds | Suit::Hearts)
+    }
+}
+
+/// A single physical playing card: a [Rank] plus a [Suit]. [RankArray]-based code throughout the
+/// rest of the crate only ever needs `Rank`; `Card` exists solely for suit-dependent side bets.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}